@@ -0,0 +1,35 @@
+//! Writes the tables compiled in by the `static-schema` feature.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The tables used when `TF2_SKU_STATIC_SCHEMA` isn't set.
+const EMPTY_TABLES: &str = "\
+static ITEMS: ::phf::Map<u32, crate::schema::SchemaItem> = ::phf::Map::new();
+static PARTICLES: ::phf::Map<u32, &'static str> = ::phf::Map::new();
+static PAINT_KITS: ::phf::Map<u32, &'static str> = ::phf::Map::new();
+";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=TF2_SKU_STATIC_SCHEMA");
+
+    if env::var_os("CARGO_FEATURE_STATIC_SCHEMA").is_none() {
+        return;
+    }
+
+    let source = match env::var("TF2_SKU_STATIC_SCHEMA") {
+        Ok(path) => {
+            println!("cargo:rerun-if-changed={path}");
+            fs::read_to_string(&path)
+                .unwrap_or_else(|error| panic!("Failed to read TF2_SKU_STATIC_SCHEMA {path}: {error}"))
+        },
+        Err(_) => {
+            println!("cargo:warning=TF2_SKU_STATIC_SCHEMA is not set, so StaticSchema has no items.");
+            EMPTY_TABLES.into()
+        },
+    };
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+
+    fs::write(out_dir.join("static_schema.rs"), source).expect("OUT_DIR is writable");
+}
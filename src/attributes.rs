@@ -0,0 +1,375 @@
+//! Conversion between SKUs and the raw attribute lists of TF2 items.
+
+use crate::SKU;
+use tf2_enum::econ_attributes::{
+    IsAustralium,
+    IsFestivized,
+    KillEater,
+    PaintkitProtoDefIndex,
+    SetAttachedParticle,
+    SupplyCrateSeries,
+    TauntAttachParticleIndex,
+    ToolTargetItem,
+    UniqueCraftIndex,
+};
+use tf2_enum::{
+    Attribute,
+    Attributes,
+    AttributeSet,
+    AttributeValue,
+    FootprintsSpell,
+    ItemAttribute,
+    KillstreakTier,
+    Killstreaker,
+    Paint,
+    PaintSpell,
+    Quality,
+    Sheen,
+    Spell,
+    StrangePart,
+    TryFromIntAttributeValue,
+    Wear,
+};
+
+/// The "texture_wear_default" attribute, used for the wear when an item has no wear of its own.
+const TEXTURE_WEAR_DEFAULT: u32 = 749;
+
+/// The item a recipe makes e.g. the Strangifier a Strangifier Chemistry Set makes. Outputs are
+/// recipe components, which hold an item rather than a value, so they aren't item attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecipeOutput {
+    /// The defindex of the output item.
+    pub defindex: u32,
+    /// The quality of the output item.
+    pub quality: Option<Quality>,
+}
+
+impl SKU {
+    /// Builds a SKU from an item's defindex, quality and attributes, as found in inventories and
+    /// the Steam Web API. Unrecognized attributes are ignored.
+    ///
+    /// Attributes are read from `float_value` when the attribute is stored as a float (e.g. the
+    /// killstreak tier) and from `value` when stored as an integer (e.g. the paint kit). A
+    /// missing `float_value` falls back to `value`, so hand-written lists such as
+    /// `(2025, 3)` also work.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    /// use tf2_enum::{ItemAttribute, Quality};
+    ///
+    /// let attributes = [
+    ///     ItemAttribute { defindex: 2025, value: 3u32.into(), float_value: None },
+    ///     ItemAttribute { defindex: 214, value: 100u32.into(), float_value: None },
+    /// ];
+    /// let sku = SKU::from_attributes(205, Quality::Unique, &attributes);
+    ///
+    /// assert_eq!(sku.to_string(), "205;6;strange;kt-3");
+    /// ```
+    pub fn from_attributes<'a, I>(defindex: i32, quality: Quality, attributes: I) -> Self
+    where
+        I: IntoIterator<Item = &'a ItemAttribute>,
+    {
+        let mut sku = SKU::new(defindex, quality);
+
+        for attribute in attributes {
+            apply_attribute(&mut sku, attribute);
+        }
+
+        // Strange quality items have a kill eater too, but aren't elevated.
+        if sku.quality == Quality::Strange {
+            sku.strange = false;
+        }
+
+        sku
+    }
+
+    /// Gets the attributes of this SKU. The defindex and quality aren't attributes, and are left
+    /// to the item.
+    ///
+    /// Elevated strange items get a kill eater with a count of 0, and effects always use the
+    /// "attach particle effect" attribute, including on taunts.
+    ///
+    /// The output of Chemistry Sets and Fabricators isn't an attribute, so it isn't included. Get
+    /// it with [`SKU::recipe_output`] and give it back with [`SKU::set_recipe_output`].
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    ///
+    /// let sku = "20005;6;td-378;od-6522;oq-6".parse::<SKU>().unwrap();
+    /// let mut converted = SKU::from_attributes(sku.defindex, sku.quality, &sku.to_attributes());
+    ///
+    /// assert_eq!(converted.to_string(), "20005;6;td-378");
+    ///
+    /// converted.set_recipe_output(sku.recipe_output());
+    ///
+    /// assert_eq!(converted, sku);
+    /// ```
+    pub fn to_attributes(&self) -> Vec<ItemAttribute> {
+        let mut attributes = Vec::new();
+
+        if let Some(particle) = self.particle {
+            attributes.push(item_attribute(SetAttachedParticle(particle)));
+        }
+
+        if let Some(killstreak_tier) = self.killstreak_tier {
+            attributes.push(item_attribute(killstreak_tier));
+        }
+
+        if let Some(sheen) = self.sheen {
+            attributes.push(item_attribute(sheen));
+        }
+
+        if let Some(killstreaker) = self.killstreaker {
+            attributes.push(item_attribute(killstreaker));
+        }
+
+        if let Some(paint) = self.paint {
+            attributes.push(item_attribute(paint));
+        }
+
+        if let Some(skin) = self.skin {
+            attributes.push(item_attribute(PaintkitProtoDefIndex(skin)));
+        }
+
+        if let Some(wear) = self.wear {
+            attributes.push(item_attribute(wear));
+        }
+
+        if self.festivized {
+            attributes.push(item_attribute(IsFestivized));
+        }
+
+        if self.australium {
+            attributes.push(item_attribute(IsAustralium));
+        }
+
+        if let Some(crate_number) = self.crate_number {
+            attributes.push(item_attribute(SupplyCrateSeries(crate_number)));
+        }
+
+        if let Some(target_defindex) = self.target_defindex {
+            attributes.push(item_attribute(ToolTargetItem(target_defindex)));
+        }
+
+        if let Some(craft_number) = self.craft_number {
+            attributes.push(item_attribute(UniqueCraftIndex(craft_number)));
+        }
+
+        if self.strange && self.quality != Quality::Strange {
+            attributes.push(item_attribute(KillEater(0)));
+        }
+
+        attributes.extend(self.spells.into_iter().map(ItemAttribute::from));
+        attributes.extend(self.strange_parts
+            .into_iter()
+            .zip(StrangePart::DEFINDEX)
+            .map(|(strange_part, defindex)| ItemAttribute {
+                defindex: *defindex,
+                value: strange_part.attribute_value(),
+                float_value: strange_part.attribute_float_value(),
+            }));
+
+        attributes
+    }
+
+    /// Gets the item a recipe makes, if this is a recipe item such as a Chemistry Set or
+    /// Fabricator.
+    pub fn recipe_output(&self) -> Option<RecipeOutput> {
+        Some(RecipeOutput {
+            defindex: self.output_defindex?,
+            quality: self.output_quality,
+        })
+    }
+
+    /// Sets the item a recipe makes, or clears it with `None`.
+    pub fn set_recipe_output(&mut self, output: Option<RecipeOutput>) {
+        self.output_defindex = output.map(|output| output.defindex);
+        self.output_quality = output.and_then(|output| output.quality);
+    }
+}
+
+/// Creates an item attribute from a typed attribute.
+fn item_attribute<T: Attribute>(attribute: T) -> ItemAttribute {
+    ItemAttribute {
+        defindex: T::DEFINDEX,
+        value: attribute.attribute_value(),
+        float_value: attribute.attribute_float_value(),
+    }
+}
+
+/// Sets the part of a SKU held by an attribute, if any.
+pub(crate) fn apply_attribute(sku: &mut SKU, attribute: &ItemAttribute) {
+    match attribute.defindex {
+        SetAttachedParticle::DEFINDEX => sku.particle = float_value(attribute),
+        TauntAttachParticleIndex::DEFINDEX => sku.particle = integer_value(attribute),
+        KillstreakTier::DEFINDEX => sku.killstreak_tier = enum_value(attribute),
+        Sheen::DEFINDEX => sku.sheen = enum_value(attribute),
+        Killstreaker::DEFINDEX => sku.killstreaker = enum_value(attribute),
+        Paint::DEFINDEX => sku.paint = enum_value(attribute),
+        PaintkitProtoDefIndex::DEFINDEX => sku.skin = integer_value(attribute),
+        Wear::DEFINDEX => sku.wear = raw_float_value(attribute)
+            .and_then(Wear::try_from_attribute_float_value),
+        TEXTURE_WEAR_DEFAULT if sku.wear.is_none() => sku.wear = raw_float_value(attribute)
+            .and_then(Wear::try_from_attribute_float_value),
+        IsFestivized::DEFINDEX => sku.festivized = true,
+        IsAustralium::DEFINDEX => sku.australium = true,
+        SupplyCrateSeries::DEFINDEX => sku.crate_number = float_value(attribute),
+        ToolTargetItem::DEFINDEX => sku.target_defindex = float_value(attribute),
+        UniqueCraftIndex::DEFINDEX => sku.craft_number = integer_value(attribute),
+        KillEater::DEFINDEX => sku.strange = true,
+        Spell::DEFINDEX_PAINT => {
+            if let Some(spell) = enum_value::<PaintSpell>(attribute) {
+                sku.spells.insert(spell.into());
+            }
+        },
+        Spell::DEFINDEX_FOOTPRINTS => {
+            if let Some(spell) = enum_value::<FootprintsSpell>(attribute) {
+                sku.spells.insert(spell.into());
+            }
+        },
+        Spell::DEFINDEX_VOICES_FROM_BELOW => {
+            sku.spells.insert(Spell::VoicesFromBelow);
+        },
+        Spell::DEFINDEX_PUMPKIN_BOMBS => {
+            sku.spells.insert(Spell::PumpkinBombs);
+        },
+        Spell::DEFINDEX_HALLOWEEN_FIRE => {
+            sku.spells.insert(Spell::HalloweenFire);
+        },
+        Spell::DEFINDEX_EXORCISM => {
+            sku.spells.insert(Spell::Exorcism);
+        },
+        defindex if StrangePart::DEFINDEX.contains(&defindex) => {
+            if let Some(strange_part) = enum_value::<StrangePart>(attribute) {
+                sku.strange_parts.insert(strange_part);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Gets the value of an attribute stored as a float.
+fn raw_float_value(attribute: &ItemAttribute) -> Option<f32> {
+    attribute.float_value.or(match &attribute.value {
+        AttributeValue::Float(value) => Some(*value),
+        AttributeValue::Integer(value) => Some(*value as f32),
+        AttributeValue::String(value) => value.parse().ok(),
+        AttributeValue::None => None,
+    })
+}
+
+/// Gets the value of an attribute stored as a float which holds a whole number e.g. a crate
+/// series.
+fn float_value(attribute: &ItemAttribute) -> Option<u32> {
+    raw_float_value(attribute)
+        .filter(|value| value.fract() == 0.0 && *value >= 0.0 && *value <= u32::MAX as f32)
+        .map(|value| value as u32)
+}
+
+/// Gets the value of an attribute stored as an integer e.g. a paint kit.
+fn integer_value(attribute: &ItemAttribute) -> Option<u32> {
+    match &attribute.value {
+        AttributeValue::Integer(value) => Some(*value),
+        AttributeValue::Float(value) if value.fract() == 0.0 && *value >= 0.0 => Some(*value as u32),
+        AttributeValue::String(value) => value.parse().ok(),
+        // The float value is the same bits as the integer.
+        _ => attribute.float_value.map(f32::to_bits),
+    }
+}
+
+/// Gets the value of an attribute stored as a float which holds an enum.
+fn enum_value<T: TryFromIntAttributeValue>(attribute: &ItemAttribute) -> Option<T> {
+    raw_float_value(attribute).and_then(T::try_from_attribute_float_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(defindex: u32, value: AttributeValue, float_value: Option<f32>) -> ItemAttribute {
+        ItemAttribute {
+            defindex,
+            value,
+            float_value,
+        }
+    }
+
+    #[test]
+    fn reads_web_api_attributes() {
+        // As returned by GetPlayerItems, with the float value's bits as the value.
+        let attributes = [
+            attribute(134, 1095761920u32.into(), Some(13.0)),
+            attribute(2025, 1077936128u32.into(), Some(3.0)),
+            attribute(2014, 1065353216u32.into(), Some(1.0)),
+            attribute(2013, 1157562368u32.into(), Some(2008.0)),
+            attribute(142, 1258291200u32.into(), Some(8421376.0)),
+            attribute(1005, 1073741824u32.into(), Some(2.0)),
+            attribute(1006, 1065353216u32.into(), Some(1.0)),
+            attribute(380, 1105199104u32.into(), Some(28.0)),
+            attribute(214, 918u32.into(), Some(f32::from_bits(918))),
+            attribute(229, 100u32.into(), Some(f32::from_bits(100))),
+            attribute(2053, 1065353216u32.into(), Some(1.0)),
+        ];
+        let sku = SKU::from_attributes(378, Quality::Unusual, &attributes);
+
+        assert_eq!(
+            sku.to_string(),
+            "378;5;u13;strange;kt-3;festive;n100;p8421376;ks-1;ke-2008;sp-28;footprints-2;voices",
+        );
+    }
+
+    #[test]
+    fn reads_skins_and_tools() {
+        let attributes = [
+            attribute(834, 102u32.into(), None),
+            attribute(725, AttributeValue::None, Some(0.6)),
+            attribute(214, 0u32.into(), None),
+        ];
+
+        assert_eq!(SKU::from_attributes(205, Quality::Strange, &attributes).to_string(), "205;11;w3;pk102");
+
+        let attributes = [
+            attribute(2012, 205u32.into(), None),
+            attribute(187, AttributeValue::Float(1.0), None),
+        ];
+
+        assert_eq!(SKU::from_attributes(6522, Quality::Unique, &attributes).to_string(), "6522;6;c1;td-205");
+    }
+
+    #[test]
+    fn round_trips_attributes() {
+        for sku in [
+            "378;5;u13;strange;kt-3;festive;n100;p8421376;ks-1;ke-2008;sp-28;sp-36;footprints-2;voices",
+            "205;15;australium;w3;pk102",
+            "6522;6;td-205",
+            "5022;6;c1",
+            "200;6;paintspell-1;pumpkinbombs;halloweenfire;exorcism",
+        ] {
+            let parsed = sku.parse::<SKU>().unwrap();
+            let attributes = parsed.to_attributes();
+
+            assert_eq!(SKU::from_attributes(parsed.defindex, parsed.quality, &attributes), parsed, "{sku}");
+        }
+    }
+
+    #[test]
+    fn round_trips_recipe_outputs() {
+        for sku in ["20005;6;td-378;od-6522;oq-6", "20002;6;kt-3;td-205;od-6526;oq-6"] {
+            let parsed = sku.parse::<SKU>().unwrap();
+            let mut converted = SKU::from_attributes(parsed.defindex, parsed.quality, &parsed.to_attributes());
+
+            assert_eq!(converted.recipe_output(), None, "{sku}");
+
+            converted.set_recipe_output(parsed.recipe_output());
+
+            assert_eq!(converted, parsed, "{sku}");
+        }
+
+        assert_eq!(
+            "20005;6;td-378;od-6522;oq-6".parse::<SKU>().unwrap().recipe_output(),
+            Some(RecipeOutput { defindex: 6522, quality: Some(Quality::Unique) }),
+        );
+    }
+}
@@ -0,0 +1,722 @@
+//! Models for backpack.tf v2 classifieds listings, websocket events and the legacy `IGetPrices`
+//! price schema, and conversion between their items and SKUs.
+//!
+//! Listings describe items by their base name and a set of attribute objects, each with an ID and
+//! a display name. The IDs are what SKUs are built from, so the names are optional when building
+//! a listing.
+
+use crate::SKU;
+use crate::currencies::Currencies;
+use crate::error::{ItemError, NameParseError};
+use crate::name::{item_name, narrow, paint_kit_name, particle_name};
+use crate::schema::ItemSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tf2_enum::{
+    AttributeSet,
+    IntoEnumIterator,
+    KillstreakTier,
+    Killstreaker,
+    Paint,
+    Quality,
+    Sheen,
+    Spell,
+    StrangePart,
+    Wear,
+};
+
+/// A classifieds listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    /// The ID of the listing e.g. "440_11111111111".
+    pub id: String,
+    /// The 64-bit Steam ID of the lister.
+    pub steamid: String,
+    /// The app ID, 440 for TF2.
+    #[serde(default)]
+    pub appid: u32,
+    /// Whether the lister is buying or selling.
+    pub intent: Intent,
+    /// The item.
+    pub item: ListingItem,
+    /// The price.
+    #[serde(default)]
+    pub currencies: Currencies,
+    /// The listing's comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The number of items wanted, for buy listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Whether the lister only accepts the listed price.
+    #[serde(default)]
+    pub buyout_only: bool,
+    /// Whether the lister prefers trade offers.
+    #[serde(default)]
+    pub trade_offers_preferred: bool,
+    /// When the item was listed, as a Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listed_at: Option<u64>,
+    /// When the listing was last bumped, as a Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bumped_at: Option<u64>,
+}
+
+/// Whether a listing is buying or selling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Intent {
+    /// Buying the item.
+    Buy,
+    /// Selling the item.
+    Sell,
+}
+
+/// The item of a listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingItem {
+    /// The defindex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defindex: Option<u32>,
+    /// The name of the item without any prefixes e.g. "Rocket Launcher".
+    pub base_name: String,
+    /// The quality.
+    pub quality: Reference,
+    /// The second quality of an item e.g. Strange on a Strange Unusual.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevated_quality: Option<Reference>,
+    /// The unusual effect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub particle: Option<Reference>,
+    /// The killstreak tier, from 1 to 3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub killstreak_tier: Option<u32>,
+    /// The killstreak sheen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sheen: Option<Reference>,
+    /// The killstreaker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub killstreaker: Option<Reference>,
+    /// The Halloween spells.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spells: Vec<ItemSpell>,
+    /// The strange parts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strange_parts: Vec<ItemStrangePart>,
+    /// The paint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paint: Option<Reference>,
+    /// Whether the item can be traded.
+    #[serde(default = "default_true")]
+    pub tradable: bool,
+    /// Whether the item can be used in crafting.
+    #[serde(default = "default_true")]
+    pub craftable: bool,
+    /// Whether the item is australium.
+    #[serde(default)]
+    pub australium: bool,
+    /// Whether the item is festivized.
+    #[serde(default)]
+    pub festivized: bool,
+    /// The skin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub texture: Option<Reference>,
+    /// The wear.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wear_tier: Option<Reference>,
+    /// The crate series.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crate_series: Option<u32>,
+    /// The target and output of a tool e.g. a Strangifier or Chemistry Set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<Recipe>,
+}
+
+/// An attribute of a listed item, referred to by its ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reference {
+    /// The ID e.g. the particle ID of an effect.
+    pub id: u32,
+    /// The display name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A Halloween spell on a listed item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemSpell {
+    /// The name of the spell e.g. "Headless Horseshoes".
+    pub name: String,
+    /// The defindex of the spell's attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defindex: Option<u32>,
+}
+
+/// A strange part on a listed item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemStrangePart {
+    /// The kill eater counted by the part.
+    pub kill_eater: Reference,
+    /// The count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u64>,
+}
+
+/// The target and output of a tool.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    /// The item the tool applies to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_item: Option<RecipeItem>,
+    /// The item the tool creates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_item: Option<RecipeItem>,
+}
+
+/// An item referred to by a recipe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeItem {
+    /// The defindex.
+    pub defindex: u32,
+    /// The name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_name: Option<String>,
+    /// The quality.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<Reference>,
+}
+
+/// An event from the backpack.tf websocket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    /// The ID of the event.
+    pub id: String,
+    /// The kind of event.
+    pub event: EventKind,
+    /// The listing updated or deleted.
+    pub payload: Listing,
+}
+
+/// The kind of a websocket event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+    /// A listing was created or changed.
+    ListingUpdate,
+    /// A listing was removed.
+    ListingDelete,
+}
+
+/// The body of a request to create a listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListingCreate {
+    /// The asset ID of the item, for sell listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The item wanted, for buy listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<ListingItem>,
+    /// The price.
+    pub currencies: Currencies,
+    /// The listing's comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl ListingCreate {
+    /// Creates a buy listing for a SKU.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::backpack_tf::ListingCreate;
+    /// use tf2_sku::currencies::Currencies;
+    ///
+    /// let sku = "5021;6".parse::<SKU>().unwrap();
+    /// let currencies = Currencies::new(0, Currencies::refined_to_weapons(60.33));
+    /// let listing = ListingCreate::buy(&sku, &Schema::default(), currencies);
+    ///
+    /// assert_eq!(listing.item.unwrap().defindex, Some(5021));
+    /// ```
+    pub fn buy<S: ItemSchema + ?Sized>(
+        sku: &SKU,
+        schema: &S,
+        currencies: Currencies,
+    ) -> Self {
+        Self {
+            id: None,
+            item: Some(ListingItem::from_sku(sku, schema)),
+            currencies,
+            details: None,
+        }
+    }
+
+    /// Creates a sell listing for an item in the lister's inventory.
+    pub fn sell(assetid: impl Into<String>, currencies: Currencies) -> Self {
+        Self {
+            id: Some(assetid.into()),
+            item: None,
+            currencies,
+            details: None,
+        }
+    }
+}
+
+impl ListingItem {
+    /// Builds a listing item from a SKU, named from the schema.
+    pub fn from_sku<S: ItemSchema + ?Sized>(sku: &SKU, schema: &S) -> Self {
+        let base_name = u32::try_from(sku.defindex)
+            .map(|defindex| item_name(schema, defindex).into_owned())
+            .unwrap_or_else(|_| format!("Item #{}", sku.defindex));
+        let recipe_item = |defindex: u32, quality: Option<Quality>| RecipeItem {
+            defindex,
+            item_name: Some(item_name(schema, defindex).into_owned()),
+            quality: quality.map(|quality| named(quality as u32, quality)),
+        };
+        let recipe = Recipe {
+            target_item: sku.target_defindex.map(|defindex| recipe_item(defindex, None)),
+            output_item: sku.output_defindex.map(|defindex| recipe_item(defindex, sku.output_quality)),
+        };
+
+        Self {
+            defindex: u32::try_from(sku.defindex).ok(),
+            base_name,
+            quality: named(sku.quality as u32, sku.quality),
+            elevated_quality: (sku.strange && sku.quality != Quality::Strange)
+                .then(|| named(Quality::Strange as u32, Quality::Strange)),
+            particle: sku.particle.map(|particle| named(particle, particle_name(schema, particle))),
+            killstreak_tier: sku.killstreak_tier.map(|tier| tier as u32),
+            sheen: sku.sheen.map(|sheen| named(sheen as u32, sheen)),
+            killstreaker: sku.killstreaker.map(|killstreaker| named(killstreaker as u32, killstreaker)),
+            spells: sku.spells
+                .into_iter()
+                .map(|spell| ItemSpell {
+                    name: spell.to_string(),
+                    defindex: Some(spell.attribute_defindex()),
+                })
+                .collect(),
+            strange_parts: sku.strange_parts
+                .into_iter()
+                .map(|strange_part| ItemStrangePart {
+                    kill_eater: named(strange_part as u32, strange_part),
+                    score: None,
+                })
+                .collect(),
+            paint: sku.paint.map(|paint| named(paint as u32, paint)),
+            tradable: true,
+            craftable: sku.craftable,
+            australium: sku.australium,
+            festivized: sku.festivized,
+            texture: sku.skin.map(|skin| named(skin, paint_kit_name(schema, skin))),
+            wear_tier: sku.wear.map(|wear| named(wear as u32, wear)),
+            crate_series: sku.crate_number,
+            recipe: (recipe != Recipe::default()).then_some(recipe),
+        }
+    }
+}
+
+impl SKU {
+    /// Converts a listed item into a SKU. Items without a defindex are looked up by their base
+    /// name, with items sharing the name narrowed by quality and crate series the way names are
+    /// parsed. Attributes with unrecognized IDs are ignored.
+    ///
+    /// # Errors
+    /// Returns [`ItemError::UnknownQuality`] if the quality isn't recognized and
+    /// [`ItemError::Name`] if the item has no defindex and its base name matches no item, or more
+    /// than one.
+    pub fn from_listing_item<S: ItemSchema + ?Sized>(
+        item: &ListingItem,
+        schema: &S,
+    ) -> Result<Self, ItemError> {
+        let quality = Quality::try_from(item.quality.id)
+            .map_err(|_| ItemError::UnknownQuality(item.quality.id))?;
+        let mut sku = SKU::new(0, quality);
+
+        if let Some(defindex) = item.defindex {
+            sku.defindex = i32::try_from(defindex).map_err(|_| ItemError::InvalidDefindex(defindex))?;
+        }
+
+        sku.strange = quality != Quality::Strange && item.elevated_quality
+            .as_ref()
+            .is_some_and(|elevated_quality| elevated_quality.id == Quality::Strange as u32);
+        sku.particle = item.particle.as_ref().map(|particle| particle.id);
+        sku.killstreak_tier = item.killstreak_tier.and_then(|tier| KillstreakTier::try_from(tier).ok());
+        sku.sheen = item.sheen.as_ref().and_then(|sheen| Sheen::try_from(sheen.id).ok());
+        sku.killstreaker = item.killstreaker
+            .as_ref()
+            .and_then(|killstreaker| Killstreaker::try_from(killstreaker.id).ok());
+        sku.paint = item.paint.as_ref().and_then(|paint| Paint::try_from(paint.id).ok());
+        sku.craftable = item.craftable;
+        sku.australium = item.australium;
+        sku.festivized = item.festivized;
+        sku.skin = item.texture.as_ref().map(|texture| texture.id);
+        sku.wear = item.wear_tier.as_ref().and_then(|wear| Wear::try_from(wear.id).ok());
+        sku.crate_number = item.crate_series;
+
+        for spell in &item.spells {
+            if let Some(spell) = Spell::iter().find(|value| value.to_string().eq_ignore_ascii_case(&spell.name)) {
+                sku.spells.insert(spell);
+            }
+        }
+
+        for strange_part in &item.strange_parts {
+            if let Ok(strange_part) = StrangePart::try_from(strange_part.kill_eater.id) {
+                sku.strange_parts.insert(strange_part);
+            }
+        }
+
+        if let Some(recipe) = &item.recipe {
+            sku.target_defindex = recipe.target_item.as_ref().map(|target| target.defindex);
+
+            if let Some(output) = &recipe.output_item {
+                sku.output_defindex = Some(output.defindex);
+                sku.output_quality = output.quality
+                    .as_ref()
+                    .and_then(|quality| Quality::try_from(quality.id).ok());
+            }
+        }
+
+        if item.defindex.is_some() {
+            return Ok(sku);
+        }
+
+        let mut skus = schema
+            .items()
+            .filter(|schema_item| schema_item.item_name == item.base_name)
+            .filter_map(|schema_item| i32::try_from(schema_item.defindex).ok())
+            .map(|defindex| SKU {
+                defindex,
+                ..sku
+            })
+            .collect::<Vec<_>>();
+
+        narrow(&mut skus, schema, item.crate_series);
+
+        match skus.as_slice() {
+            [] => Err(NameParseError::UnknownItem(item.base_name.clone()).into()),
+            [sku] => Ok(*sku),
+            _ => Err(NameParseError::Ambiguous(skus).into()),
+        }
+    }
+}
+
+/// The response of the legacy `IGetPrices/v4` API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPricesResponse {
+    /// The response.
+    pub response: PriceSchema,
+}
+
+/// The prices of every item, indexed by item name.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PriceSchema {
+    /// Whether the request succeeded, 1 if it did.
+    #[serde(default)]
+    pub success: u32,
+    /// When the prices were generated, as a Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_time: Option<u64>,
+    /// The value of one refined metal in US dollars.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_usd_value: Option<f64>,
+    /// The items, by name e.g. "Rocket Launcher".
+    #[serde(default)]
+    pub items: BTreeMap<String, PriceItem>,
+}
+
+/// The prices of an item.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PriceItem {
+    /// The defindexes sharing the item's name and prices e.g. the stock and upgradeable
+    /// versions of a weapon.
+    #[serde(default)]
+    pub defindex: Vec<u32>,
+    /// The prices by quality ID.
+    #[serde(default)]
+    pub prices: BTreeMap<String, QualityPrices>,
+}
+
+/// The prices of an item in a quality, by tradability.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct QualityPrices {
+    /// The prices of tradable items.
+    #[serde(default, rename = "Tradable", skip_serializing_if = "Option::is_none")]
+    pub tradable: Option<CraftabilityPrices>,
+    /// The prices of items which can't be traded.
+    #[serde(default, rename = "Non-Tradable", skip_serializing_if = "Option::is_none")]
+    pub non_tradable: Option<CraftabilityPrices>,
+}
+
+/// The prices of an item by craftability.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CraftabilityPrices {
+    /// The prices of craftable items.
+    #[serde(default, rename = "Craftable", skip_serializing_if = "Option::is_none")]
+    pub craftable: Option<PriceIndexes>,
+    /// The prices of items which can't be used in crafting.
+    #[serde(default, rename = "Non-Craftable", skip_serializing_if = "Option::is_none")]
+    pub non_craftable: Option<PriceIndexes>,
+}
+
+/// The prices of an item by price index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PriceIndexes {
+    /// The price of an item without a price index, sent as a list of one.
+    Single(Vec<Price>),
+    /// The prices by price index e.g. by effect for Unusual items.
+    Indexed(BTreeMap<String, Price>),
+}
+
+impl PriceIndexes {
+    /// Iterates over the prices and their price indexes. Prices of items without a price index
+    /// have an empty one.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, &Price)> + '_> {
+        match self {
+            PriceIndexes::Single(prices) => Box::new(prices.iter().map(|price| ("", price))),
+            PriceIndexes::Indexed(prices) => Box::new(prices
+                .iter()
+                // An index of 0 is the same as none.
+                .map(|(price_index, price)| match price_index.as_str() {
+                    "0" => ("", price),
+                    price_index => (price_index, price),
+                })),
+        }
+    }
+}
+
+/// A price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Price {
+    /// The value, or the low end of a range.
+    pub value: f64,
+    /// The high end of a range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_high: Option<f64>,
+    /// The value in refined metal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_raw: Option<f64>,
+    /// The currency of the value e.g. "keys" or "metal".
+    pub currency: String,
+    /// The change in value from the previous price, in refined metal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difference: Option<f64>,
+    /// When the price was last changed, as a Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<u64>,
+    /// Whether the price is of the australium version of the item.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub australium: bool,
+}
+
+impl PriceSchema {
+    /// Flattens the prices into a map keyed by SKU. Every defindex of an item gets the item's
+    /// prices.
+    ///
+    /// SKUs don't record tradability, so only the prices of tradable items are included; the
+    /// prices of non-tradable items are left out, and can be read from
+    /// [`QualityPrices::non_tradable`]. Prices with an unknown quality or a price index not valid
+    /// for the item are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::backpack_tf::GetPricesResponse;
+    ///
+    /// let json = r#"{
+    ///     "response": {
+    ///         "success": 1,
+    ///         "items": {
+    ///             "Mann Co. Supply Crate Key": {
+    ///                 "defindex": [5021],
+    ///                 "prices": {
+    ///                     "6": { "Tradable": { "Craftable": [{ "value": 60.33, "currency": "metal" }] } }
+    ///                 }
+    ///             }
+    ///         }
+    ///     }
+    /// }"#;
+    /// let response = serde_json::from_str::<GetPricesResponse>(json).unwrap();
+    /// let prices = response.response.flatten(&Schema::default());
+    ///
+    /// assert_eq!(prices[&"5021;6".parse::<SKU>().unwrap()].value, 60.33);
+    /// ```
+    pub fn flatten<S: ItemSchema + ?Sized>(&self, schema: &S) -> HashMap<SKU, Price> {
+        let mut flattened = HashMap::new();
+
+        for item in self.items.values() {
+            for (quality, prices) in &item.prices {
+                let quality = quality.parse::<u32>().ok().and_then(|quality| Quality::try_from(quality).ok());
+                let (Some(quality), Some(tradable)) = (quality, &prices.tradable) else {
+                    continue;
+                };
+
+                for (craftable, indexes) in [(true, &tradable.craftable), (false, &tradable.non_craftable)] {
+                    for (price_index, price) in indexes.iter().flat_map(PriceIndexes::iter) {
+                        for defindex in &item.defindex {
+                            let Ok(mut sku) = SKU::from_price_index(*defindex, quality, true, craftable, price_index, schema) else {
+                                continue;
+                            };
+
+                            sku.australium = price.australium;
+                            flattened.insert(sku, price.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        flattened
+    }
+}
+
+/// Creates a reference with a display name.
+fn named(id: u32, name: impl ToString) -> Reference {
+    Reference {
+        id,
+        name: Some(name.to_string()),
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn events() -> Vec<Event> {
+        let json = std::fs::read_to_string("tests/fixtures/backpack_tf_events.json").unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn converts_websocket_events() {
+        let events = events();
+        let skus = events
+            .iter()
+            .map(|event| SKU::from_listing_item(&event.payload.item, &schema()).unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(events[0].event, EventKind::ListingUpdate);
+        assert_eq!(events[0].payload.intent, Intent::Sell);
+        assert_eq!(events[0].payload.currencies, Currencies::new(1, 2 * Currencies::ONE_REF + 3 * Currencies::ONE_SCRAP));
+        assert_eq!(events[2].event, EventKind::ListingDelete);
+        assert_eq!(skus, [
+            "205;11;australium;kt-3;ks-1;ke-2008;sp-28",
+            "378;5;u13;uncraftable;strange;p8421376;footprints-2",
+            "16102;15;w3;pk102",
+            "6522;6;td-205",
+        ]);
+    }
+
+    #[test]
+    fn round_trips_listing_items() {
+        for sku in [
+            "205;11;australium;kt-3;ks-1;ke-2008;sp-28;sp-36",
+            "378;5;u13;uncraftable;strange;festive;p8421376;footprints-2;voices",
+            "16102;15;w3;pk102",
+            "5022;6;c1",
+            "20005;6;td-378;od-6522;oq-6",
+        ] {
+            let parsed = sku.parse::<SKU>().unwrap();
+            let item = ListingItem::from_sku(&parsed, &schema());
+
+            assert_eq!(SKU::from_listing_item(&item, &schema()).unwrap(), parsed, "{sku}");
+        }
+    }
+
+    #[test]
+    fn builds_listing_payloads() {
+        let sku = "378;5;u13;strange".parse::<SKU>().unwrap();
+        let currencies = Currencies::new(50, 0);
+        let json = serde_json::to_value(ListingCreate::buy(&sku, &schema(), currencies)).unwrap();
+
+        assert_eq!(json, serde_json::json!({
+            "item": {
+                "defindex": 378,
+                "baseName": "Team Captain",
+                "quality": { "id": 5, "name": "Unusual" },
+                "elevatedQuality": { "id": 11, "name": "Strange" },
+                "particle": { "id": 13, "name": "Burning Flames" },
+                "tradable": true,
+                "craftable": true,
+                "australium": false,
+                "festivized": false,
+            },
+            "currencies": { "keys": 50, "metal": 0 },
+        }));
+
+        let json = serde_json::to_value(ListingCreate::sell("11111111111", currencies)).unwrap();
+
+        assert_eq!(json, serde_json::json!({
+            "id": "11111111111",
+            "currencies": { "keys": 50, "metal": 0 },
+        }));
+    }
+
+    #[test]
+    fn looks_up_items_by_base_name() {
+        let mut item = events()[0].payload.item.clone();
+
+        item.defindex = None;
+
+        // Stock items are only Normal quality, so a Strange "Rocket Launcher" is the upgradeable one.
+        assert_eq!(SKU::from_listing_item(&item, &schema()).unwrap().defindex, 205);
+
+        item.base_name = "Shotgun".into();
+        item.quality = Reference {
+            id: Quality::Normal as u32,
+            name: None,
+        };
+
+        assert!(matches!(
+            SKU::from_listing_item(&item, &schema()),
+            Err(ItemError::Name(NameParseError::Ambiguous(skus))) if skus.len() == 2,
+        ));
+
+        item.base_name = "Unknown Hat".into();
+
+        assert_eq!(
+            SKU::from_listing_item(&item, &schema()),
+            Err(ItemError::Name(NameParseError::UnknownItem("Unknown Hat".into()))),
+        );
+    }
+
+    #[test]
+    fn flattens_price_schema() {
+        let json = std::fs::read_to_string("tests/fixtures/igetprices.json").unwrap();
+        let response = serde_json::from_str::<GetPricesResponse>(&json).unwrap();
+        let prices = response.response.flatten(&schema());
+        let price = |sku: &str| prices.get(&sku.parse::<SKU>().unwrap()).map(|price| (price.value, price.currency.as_str()));
+        let mut skus = prices.keys().map(|sku| sku.to_string()).collect::<Vec<_>>();
+
+        skus.sort();
+
+        assert_eq!(skus, [
+            "18;11",
+            "205;11",
+            "205;11;australium",
+            "378;5;u13",
+            "378;5;u14",
+            "378;6",
+            "378;6;uncraftable",
+            "5021;6",
+            "5022;6;c1",
+            "6522;6;td-205",
+            "6527;6;kt-3;td-205",
+        ]);
+        assert_eq!(price("205;11"), Some((5.0, "metal")));
+        assert_eq!(price("205;11;australium"), Some((30.0, "keys")));
+        assert_eq!(price("378;5;u13"), Some((250.0, "keys")));
+        assert_eq!(price("378;6;uncraftable"), Some((1.33, "metal")));
+    }
+}
@@ -0,0 +1,31 @@
+//! Generates static schema tables from a JSON schema snapshot.
+//!
+//! Usage: `tf2-sku-codegen <snapshot.json> <output.rs>`
+
+use std::fs;
+use std::process::ExitCode;
+use tf2_sku::Schema;
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let [input, output] = args.as_slice() else {
+        eprintln!("Usage: tf2-sku-codegen <snapshot.json> <output.rs>");
+        return ExitCode::FAILURE;
+    };
+    let schema = match fs::read_to_string(input)
+        .map_err(|error| error.to_string())
+        .and_then(|json| serde_json::from_str::<Schema>(&json).map_err(|error| error.to_string())) {
+        Ok(schema) => schema,
+        Err(error) => {
+            eprintln!("Failed to read snapshot {input}: {error}");
+            return ExitCode::FAILURE;
+        },
+    };
+
+    if let Err(error) = fs::write(output, tf2_sku::codegen::generate(&schema)) {
+        eprintln!("Failed to write {output}: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,314 @@
+//! Making change in keys and metal.
+//!
+//! Metal is given from refined down to scrap, then craft weapons when allowed. Each of these is
+//! worth a whole number of the next, so giving the largest pieces first finds an exact amount
+//! whenever the pieces held can make it.
+
+use crate::SKU;
+use crate::currencies::Currencies;
+use crate::error::ChangeError;
+use crate::schema::ItemSchema;
+use std::collections::{BTreeMap, HashMap};
+use tf2_enum::{CraftClass, Quality};
+
+/// Options for [`make_change`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ChangeOptions {
+    /// The price of a key in weapons. When set, keys short of what is owed are paid in metal, and
+    /// keys beyond what is owed are given when metal runs short. It must be more than zero.
+    pub key_price: Option<i64>,
+    /// Whether craft weapons can be given as half a scrap each.
+    pub craft_weapons: bool,
+    /// Whether more than is owed can be given, asking for the difference back.
+    pub allow_change: bool,
+}
+
+/// What to give for an amount owed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Change {
+    /// The items to give, by SKU.
+    pub give: BTreeMap<SKU, u32>,
+    /// The change to ask for, when more is given than is owed.
+    pub ask: Currencies,
+}
+
+/// Works out which of the held keys and metal to give for an amount owed.
+///
+/// Keys pay the keys part of the amount and metal the metal part, so keys are kept unless the
+/// metal held runs short. Only craftable Unique keys, metal and, with
+/// [`ChangeOptions::craft_weapons`], craft weapons in the schema are given.
+///
+/// # Errors
+/// Returns a [`ChangeError`] explaining why the amount can't be paid.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{SKU, Schema};
+/// use tf2_sku::change::{make_change, ChangeOptions};
+/// use tf2_sku::currencies::{Currencies, KEY_SKU, REFINED_SKU, SCRAP_SKU};
+/// use std::collections::HashMap;
+///
+/// let key = KEY_SKU.parse::<SKU>().unwrap();
+/// let refined = REFINED_SKU.parse::<SKU>().unwrap();
+/// let scrap = SCRAP_SKU.parse::<SKU>().unwrap();
+/// let held = HashMap::from([(key, 3), (refined, 5), (scrap, 4)]);
+/// let owed = "1 key, 2.22 ref".parse::<Currencies>().unwrap();
+/// let change = make_change(&held, owed, &Schema::default(), ChangeOptions::default()).unwrap();
+///
+/// assert_eq!(change.give[&key], 1);
+/// assert_eq!(change.give[&refined], 2);
+/// assert_eq!(change.give[&scrap], 2);
+/// ```
+pub fn make_change<S: ItemSchema + ?Sized>(
+    held: &HashMap<SKU, u32>,
+    owed: Currencies,
+    schema: &S,
+    options: ChangeOptions,
+) -> Result<Change, ChangeError> {
+    if owed.keys < 0 || owed.metal < 0 {
+        return Err(ChangeError::NegativeAmount(owed));
+    }
+
+    if let Some(key_price) = options.key_price.filter(|key_price| *key_price <= 0) {
+        return Err(ChangeError::InvalidKeyPrice(key_price));
+    }
+
+    let key = SKU::new(5021, Quality::Unique);
+    let keys_held = i64::from(held.get(&key).copied().unwrap_or_default());
+    let pieces = metal_pieces(held, schema, options.craft_weapons);
+    let metal_held = pieces
+        .iter()
+        .map(|(_, value, count)| value * i64::from(*count))
+        .sum::<i64>();
+    let mut keys = owed.keys;
+    let mut metal = owed.metal;
+
+    if keys_held < keys {
+        let key_price = options.key_price.ok_or(ChangeError::NotEnoughKeys {
+            owed: keys,
+            held: keys_held,
+        })?;
+
+        metal += (keys - keys_held) * key_price;
+        keys = keys_held;
+    }
+
+    if metal_held < metal {
+        let not_enough_metal = ChangeError::NotEnoughMetal {
+            owed: metal,
+            held: metal_held,
+        };
+        let Some(key_price) = options.key_price else {
+            return Err(not_enough_metal);
+        };
+        let extra_keys = (metal - metal_held + key_price - 1) / key_price;
+
+        if extra_keys > keys_held - keys {
+            return Err(not_enough_metal);
+        }
+
+        // Keys worth more than the metal owed can only be given with change back.
+        if extra_keys * key_price > metal && !options.allow_change {
+            return Err(ChangeError::NoExactChange {
+                owed: metal,
+            });
+        }
+
+        keys += extra_keys;
+        metal -= extra_keys * key_price;
+    }
+
+    let mut change = Change::default();
+
+    if keys > 0 {
+        change.give.insert(key, keys as u32);
+    }
+
+    if metal <= 0 {
+        change.ask = Currencies::new(0, -metal);
+        return Ok(change);
+    }
+
+    let given = (metal..=metal_held)
+        .take(if options.allow_change { usize::MAX } else { 1 })
+        .find_map(|amount| give_exactly(&pieces, amount).map(|given| (amount, given)));
+    let Some((amount, given)) = given else {
+        return Err(ChangeError::NoExactChange {
+            owed: metal,
+        });
+    };
+
+    change.give.extend(given);
+    change.ask = Currencies::new(0, amount - metal);
+
+    Ok(change)
+}
+
+/// Gets the metal pieces held, largest first, as their SKU, value in weapons and count.
+fn metal_pieces<S: ItemSchema + ?Sized>(
+    held: &HashMap<SKU, u32>,
+    schema: &S,
+    craft_weapons: bool,
+) -> Vec<(SKU, i64, u32)> {
+    let mut pieces = held
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .filter_map(|(sku, count)| {
+            let value = match Currencies::from_sku(sku) {
+                Some(value) if value.keys == 0 => value.metal,
+                Some(_) => return None,
+                None if craft_weapons && is_craft_weapon(sku, schema) => Currencies::ONE_WEAPON,
+                None => return None,
+            };
+
+            Some((*sku, value, *count))
+        })
+        .collect::<Vec<_>>();
+
+    pieces.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    pieces
+}
+
+/// Checks whether a SKU is a plain craftable Unique weapon that can be smelted.
+fn is_craft_weapon<S: ItemSchema + ?Sized>(sku: &SKU, schema: &S) -> bool {
+    *sku == SKU::new(sku.defindex, Quality::Unique) && schema
+        .sku_item(sku)
+        .is_some_and(|item| item.craft_class == Some(CraftClass::Weapon))
+}
+
+/// Gives exactly `amount` weapons of metal from the largest pieces down.
+fn give_exactly(pieces: &[(SKU, i64, u32)], mut amount: i64) -> Option<BTreeMap<SKU, u32>> {
+    let mut given = BTreeMap::new();
+
+    for (sku, value, count) in pieces {
+        let taken = (amount / value).min(i64::from(*count));
+
+        if taken > 0 {
+            given.insert(*sku, taken as u32);
+            amount -= taken * value;
+        }
+    }
+
+    (amount == 0).then_some(given)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn sku(sku: &str) -> SKU {
+        sku.parse().unwrap()
+    }
+
+    fn held(items: &[(&str, u32)]) -> HashMap<SKU, u32> {
+        items.iter().map(|(item, count)| (sku(item), *count)).collect()
+    }
+
+    fn give(items: &[(&str, u32)]) -> BTreeMap<SKU, u32> {
+        items.iter().map(|(item, count)| (sku(item), *count)).collect()
+    }
+
+    fn make_change(held: &HashMap<SKU, u32>, owed: &str, options: ChangeOptions) -> Result<Change, ChangeError> {
+        super::make_change(held, owed.parse().unwrap(), &schema(), options)
+    }
+
+    #[test]
+    fn gives_exact_change() {
+        let held = held(&[("5021;6", 2), ("5002;6", 3), ("5001;6", 2), ("5000;6", 5), ("5002;6;uncraftable", 9)]);
+        let change = make_change(&held, "1 key, 2.33 ref", ChangeOptions::default()).unwrap();
+
+        assert_eq!(change.give, give(&[("5021;6", 1), ("5002;6", 2), ("5001;6", 1)]));
+        assert_eq!(change.ask, Currencies::default());
+
+        // Two refined would overshoot 1.77 ref, so the rest comes from smaller pieces.
+        let change = make_change(&held, "1.77 ref", ChangeOptions::default()).unwrap();
+
+        assert_eq!(change.give, give(&[("5002;6", 1), ("5001;6", 2), ("5000;6", 1)]));
+    }
+
+    #[test]
+    fn asks_for_change() {
+        let held = held(&[("5002;6", 1)]);
+        let options = ChangeOptions {
+            allow_change: true,
+            ..ChangeOptions::default()
+        };
+        let change = make_change(&held, "0.11 ref", options).unwrap();
+
+        assert_eq!(change.give, give(&[("5002;6", 1)]));
+        assert_eq!(change.ask, Currencies::new(0, 16));
+        assert_eq!(
+            make_change(&held, "0.11 ref", ChangeOptions::default()),
+            Err(ChangeError::NoExactChange { owed: 2 }),
+        );
+    }
+
+    #[test]
+    fn trades_keys_for_metal_at_the_key_price() {
+        let options = ChangeOptions {
+            key_price: Some(1086),
+            allow_change: true,
+            ..ChangeOptions::default()
+        };
+        let change = make_change(&held(&[("5021;6", 2), ("5000;6", 1)]), "1 ref", options).unwrap();
+
+        assert_eq!(change.give, give(&[("5021;6", 1)]));
+        assert_eq!(change.ask, Currencies::new(0, 1068));
+
+        // 61 ref is a key and 2 rec at the key price, which is given exactly without change.
+        let exact = ChangeOptions {
+            allow_change: false,
+            ..options
+        };
+        let change = make_change(&held(&[("5021;6", 2), ("5001;6", 2)]), "61 ref", exact).unwrap();
+
+        assert_eq!(change.give, give(&[("5021;6", 1), ("5001;6", 2)]));
+        assert_eq!(change.ask, Currencies::default());
+        assert_eq!(
+            make_change(&held(&[("5021;6", 2), ("5000;6", 1)]), "1 ref", exact),
+            Err(ChangeError::NoExactChange { owed: 18 }),
+        );
+
+        let change = make_change(&held(&[("5002;6", 61)]), "1 key", options).unwrap();
+
+        assert_eq!(change.give, give(&[("5002;6", 61)]));
+        assert_eq!(change.ask, Currencies::new(0, 12));
+        assert_eq!(
+            make_change(&held(&[("5002;6", 61)]), "1 key", ChangeOptions::default()),
+            Err(ChangeError::NotEnoughKeys { owed: 1, held: 0 }),
+        );
+
+        for key_price in [0, -1086] {
+            let options = ChangeOptions {
+                key_price: Some(key_price),
+                ..options
+            };
+
+            assert_eq!(
+                make_change(&held(&[("5002;6", 61)]), "1 key", options),
+                Err(ChangeError::InvalidKeyPrice(key_price)),
+            );
+        }
+    }
+
+    #[test]
+    fn gives_craft_weapons() {
+        let held = held(&[("5000;6", 1), ("199;6", 1), ("200;6", 2), ("378;6", 4), ("205;11", 4)]);
+        let options = ChangeOptions {
+            craft_weapons: true,
+            ..ChangeOptions::default()
+        };
+        let change = make_change(&held, "0.22 ref", options).unwrap();
+
+        assert_eq!(change.give, give(&[("5000;6", 1), ("199;6", 1), ("200;6", 1)]));
+        assert_eq!(
+            make_change(&held, "0.22 ref", ChangeOptions::default()),
+            Err(ChangeError::NotEnoughMetal { owed: 4, held: 2 }),
+        );
+        assert_eq!(
+            make_change(&held, "-1 key", options),
+            Err(ChangeError::NegativeAmount(Currencies::new(-1, 0))),
+        );
+    }
+}
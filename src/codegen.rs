@@ -0,0 +1,136 @@
+//! Generates Rust source for static schema tables from a [`Schema`] snapshot.
+//!
+//! The output is meant to be compiled into this crate with the `static-schema` feature, which
+//! exposes it as [`StaticSchema`](crate::static_schema::StaticSchema). Use the `tf2-sku-codegen`
+//! binary to generate it from a JSON snapshot:
+//!
+//! ```text
+//! cargo run --features codegen --bin tf2-sku-codegen -- schema.json static_schema.rs
+//! TF2_SKU_STATIC_SCHEMA=$PWD/static_schema.rs cargo build --features static-schema
+//! ```
+
+use crate::schema::{Schema, SchemaItem};
+use std::fmt::Write;
+
+/// Generates the source for the static tables. Each table is a perfect hash map:
+/// - `ITEMS`: defindex to [`SchemaItem`].
+/// - `PARTICLES`: particle ID to effect name.
+/// - `PAINT_KITS`: paint kit ID to paint kit name.
+pub fn generate(schema: &Schema) -> String {
+    let mut items = phf_codegen::Map::new();
+    let mut particles = phf_codegen::Map::new();
+    let mut paint_kits = phf_codegen::Map::new();
+
+    for (defindex, item) in &schema.items {
+        items.entry(*defindex, &item_expr(item));
+    }
+
+    for (id, name) in &schema.particles {
+        particles.entry(*id, &format!("{name:?}"));
+    }
+
+    for (id, name) in &schema.paint_kits {
+        paint_kits.entry(*id, &format!("{name:?}"));
+    }
+
+    let mut out = String::from("// @generated by tf2-sku-codegen. Do not edit.\n\n");
+
+    // Writing to a String cannot fail.
+    let _ = writeln!(
+        out,
+        "static ITEMS: ::phf::Map<u32, crate::schema::SchemaItem> = {};\n",
+        items.build(),
+    );
+    let _ = writeln!(
+        out,
+        "static PARTICLES: ::phf::Map<u32, &'static str> = {};\n",
+        particles.build(),
+    );
+    let _ = writeln!(
+        out,
+        "static PAINT_KITS: ::phf::Map<u32, &'static str> = {};",
+        paint_kits.build(),
+    );
+    out
+}
+
+/// A constant expression constructing `item`.
+fn item_expr(item: &SchemaItem) -> String {
+    let capabilities = item.capabilities
+        .iter()
+        .map(|capability| format!("crate::tf2_enum::Capability::{capability:?}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "crate::schema::SchemaItem {{ \
+            defindex: {}, \
+            name: {}, \
+            item_name: {}, \
+            proper_name: {}, \
+            item_class: {}, \
+            item_quality: crate::tf2_enum::Quality::{:?}, \
+            craft_class: {}, \
+            item_slot: {}, \
+            tool_type: {}, \
+            crate_series: {:?}, \
+            paint_kit: {:?}, \
+            capabilities: ::std::borrow::Cow::Borrowed(&[{}]) \
+        }}",
+        item.defindex,
+        cow_expr(&item.name),
+        cow_expr(&item.item_name),
+        item.proper_name,
+        cow_expr(&item.item_class),
+        item.item_quality,
+        option_expr(item.craft_class.map(|c| format!("crate::tf2_enum::CraftClass::{c:?}"))),
+        option_expr(item.item_slot.map(|s| format!("crate::tf2_enum::ItemSlot::{s:?}"))),
+        option_expr(item.tool_type.as_deref().map(cow_expr)),
+        item.crate_series,
+        item.paint_kit,
+        capabilities,
+    )
+}
+
+fn cow_expr(s: &str) -> String {
+    format!("::std::borrow::Cow::Borrowed({s:?})")
+}
+
+fn option_expr(expr: Option<String>) -> String {
+    match expr {
+        Some(expr) => format!("Some({expr})"),
+        None => "None".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_tables() {
+        let schema = Schema::load_items_game("tests/fixtures/items_game.txt").unwrap();
+        let source = generate(&schema);
+
+        assert!(source.contains("static ITEMS: ::phf::Map<u32, crate::schema::SchemaItem>"));
+        assert!(source.contains("item_name: ::std::borrow::Cow::Borrowed(\"#TF_Weapon_RocketLauncher\")"));
+        assert!(source.contains("crate::tf2_enum::Capability::CanKillstreakify"));
+        assert!(source.contains("\"#Attrib_Particle13\""));
+    }
+
+    #[test]
+    fn matches_static_schema_fixture() {
+        // The static schema tests compile against this fixture, so it must stay generated.
+        let schema = Schema::load_items_game("tests/fixtures/items_game.txt").unwrap();
+        let fixture = std::fs::read_to_string("tests/fixtures/static_schema.rs").unwrap();
+
+        assert_eq!(generate(&schema), fixture);
+    }
+
+    #[test]
+    fn output_is_deterministic() {
+        let schema = Schema::load_items_game("tests/fixtures/items_game.txt").unwrap();
+
+        assert_eq!(generate(&schema), generate(&schema));
+    }
+}
@@ -0,0 +1,580 @@
+//! Models for Steam Community inventories and trade offers, and conversion of their item
+//! descriptions into SKUs.
+//!
+//! Both the community inventory endpoint (`/inventory/<steamid>/440/2`) and
+//! `IEconService/GetTradeOffers` describe items as assets pointing at shared descriptions. The
+//! description holds the item's name, tags and the text lines shown under the item, from which the
+//! parts of a SKU that aren't in the name (effects, spells, strange parts, killstreak sheens and
+//! craftability) are read.
+
+use crate::SKU;
+use crate::error::{ItemError, NameParseError};
+use crate::locale::Localization;
+use crate::name::{English, Vocabulary};
+use crate::schema::ItemSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use tf2_enum::{
+    AttributeSet,
+    IntoEnumIterator,
+    Paint,
+    Quality,
+    Spell,
+    StrangePart,
+    Wear,
+};
+
+/// The line marking an item which can't be used in crafting, as it appears inside parentheses.
+const NOT_USABLE_IN_CRAFTING: &str = "Usable in Crafting";
+
+/// An inventory from the Steam Community inventory endpoint.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{SKU, Schema};
+/// use tf2_sku::community::Inventory;
+///
+/// let json = r#"{
+///     "assets": [{ "appid": 440, "contextid": "2", "assetid": "1", "classid": "2", "instanceid": "0", "amount": "1" }],
+///     "descriptions": [{
+///         "appid": 440,
+///         "classid": "2",
+///         "instanceid": "0",
+///         "market_hash_name": "Item #5021",
+///         "tradable": 1,
+///         "marketable": 1,
+///         "tags": [{ "category": "Quality", "internal_name": "Unique", "localized_tag_name": "Unique" }],
+///         "app_data": { "def_index": "5021", "quality": "6" }
+///     }],
+///     "total_inventory_count": 1,
+///     "success": 1
+/// }"#;
+/// let inventory = serde_json::from_str::<Inventory>(json).unwrap();
+/// let (_asset, description) = inventory.items().next().unwrap();
+/// let sku = SKU::from_description(description, &Schema::default()).unwrap();
+///
+/// assert_eq!(sku.to_string(), "5021;6");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    /// The items in the inventory.
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+    /// The descriptions of the items, shared between identical items.
+    #[serde(default)]
+    pub descriptions: Vec<Description>,
+    /// The number of items in the whole inventory, across every page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_inventory_count: Option<u32>,
+    /// Whether there are more pages after this one.
+    #[serde(default, deserialize_with = "deserialize_bool", skip_serializing_if = "std::ops::Not::not")]
+    pub more_items: bool,
+    /// The asset ID to start the next page from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_assetid: Option<String>,
+    /// Whether the request succeeded.
+    #[serde(default, deserialize_with = "deserialize_bool")]
+    pub success: bool,
+}
+
+impl Inventory {
+    /// Gets the description of an asset.
+    pub fn description(&self, asset: &Asset) -> Option<&Description> {
+        find_description(&self.descriptions, asset)
+    }
+
+    /// Iterates over the assets and their descriptions. Assets without a description are skipped.
+    pub fn items(&self) -> impl Iterator<Item = (&Asset, &Description)> + '_ {
+        self.assets
+            .iter()
+            .filter_map(|asset| Some((asset, self.description(asset)?)))
+    }
+}
+
+/// The response of `IEconService/GetTradeOffers` with `get_descriptions` set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetTradeOffersResponse {
+    /// The response.
+    pub response: TradeOffers,
+}
+
+/// Trade offers and the descriptions of the items in them.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TradeOffers {
+    /// The offers sent.
+    #[serde(default)]
+    pub trade_offers_sent: Vec<TradeOffer>,
+    /// The offers received.
+    #[serde(default)]
+    pub trade_offers_received: Vec<TradeOffer>,
+    /// The descriptions of the items in the offers.
+    #[serde(default)]
+    pub descriptions: Vec<Description>,
+    /// The cursor for the next page, or 0 on the last page.
+    #[serde(default)]
+    pub next_cursor: u32,
+}
+
+impl TradeOffers {
+    /// Gets the description of an asset.
+    pub fn description(&self, asset: &Asset) -> Option<&Description> {
+        find_description(&self.descriptions, asset)
+    }
+}
+
+/// A trade offer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeOffer {
+    /// The ID of the offer.
+    pub tradeofferid: String,
+    /// The 32-bit account ID of the other party.
+    pub accountid_other: u32,
+    /// The message sent with the offer.
+    #[serde(default)]
+    pub message: String,
+    /// The state of the offer e.g. 2 for active and 3 for accepted.
+    pub trade_offer_state: u32,
+    /// The items given away by this account.
+    #[serde(default)]
+    pub items_to_give: Vec<Asset>,
+    /// The items received by this account.
+    #[serde(default)]
+    pub items_to_receive: Vec<Asset>,
+    /// Whether this account sent the offer.
+    #[serde(default)]
+    pub is_our_offer: bool,
+    /// When the offer was created, as a Unix timestamp.
+    #[serde(default)]
+    pub time_created: u64,
+    /// When the offer was last updated, as a Unix timestamp.
+    #[serde(default)]
+    pub time_updated: u64,
+    /// When the offer expires, as a Unix timestamp.
+    #[serde(default)]
+    pub expiration_time: u64,
+}
+
+/// An item in an inventory or trade offer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Asset {
+    /// The app ID, 440 for TF2.
+    pub appid: u32,
+    /// The inventory context, 2 for TF2 items.
+    pub contextid: String,
+    /// The ID of the item.
+    pub assetid: String,
+    /// The class ID, which together with the instance ID identifies the description.
+    pub classid: String,
+    /// The instance ID.
+    pub instanceid: String,
+    /// The number of items in the stack.
+    #[serde(deserialize_with = "deserialize_number")]
+    pub amount: u32,
+    /// Whether the item is no longer in the inventory it was offered from.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub missing: bool,
+}
+
+/// The description of an item.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Description {
+    /// The app ID, 440 for TF2.
+    pub appid: u32,
+    /// The class ID.
+    pub classid: String,
+    /// The instance ID.
+    pub instanceid: String,
+    /// The displayed name, which may be a custom name.
+    #[serde(default)]
+    pub name: String,
+    /// The name on the Steam Community Market e.g. "Strange Professional Killstreak Rocket
+    /// Launcher".
+    #[serde(default)]
+    pub market_hash_name: String,
+    /// The color of the name as a hexadecimal string without a leading "#".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_color: Option<String>,
+    /// The type line e.g. "Strange Rocket Launcher - Kills: 0".
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+    /// Whether the item can be traded.
+    #[serde(default, deserialize_with = "deserialize_bool")]
+    pub tradable: bool,
+    /// Whether the item can be sold on the Steam Community Market.
+    #[serde(default, deserialize_with = "deserialize_bool")]
+    pub marketable: bool,
+    /// The text lines shown under the item.
+    #[serde(default)]
+    pub descriptions: Vec<DescriptionLine>,
+    /// The tags e.g. the quality and exterior.
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// The game's own data for the item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_data: Option<AppData>,
+}
+
+impl Description {
+    /// Gets the localized name of the tag in a category e.g. "Field-Tested" for "Exterior".
+    pub fn tag(&self, category: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.category == category)
+            .map(|tag| tag.localized_tag_name.as_str())
+    }
+}
+
+/// A text line in a description.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DescriptionLine {
+    /// The kind of text, usually "text" or "html".
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// The text.
+    pub value: String,
+    /// The color of the text as a hexadecimal string without a leading "#".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// A tag of a description.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Tag {
+    /// The category e.g. "Quality".
+    pub category: String,
+    /// The internal name of the tag e.g. "strange".
+    pub internal_name: String,
+    /// The localized name of the tag e.g. "Strange".
+    #[serde(default)]
+    pub localized_tag_name: String,
+    /// The localized name of the category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localized_category_name: Option<String>,
+    /// The color of the tag as a hexadecimal string without a leading "#".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// The game's own data for an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AppData {
+    /// The defindex.
+    #[serde(default, deserialize_with = "deserialize_option_number", skip_serializing_if = "Option::is_none")]
+    pub def_index: Option<u32>,
+    /// The quality.
+    #[serde(default, deserialize_with = "deserialize_option_number", skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u32>,
+}
+
+impl SKU {
+    /// Converts an item description into a SKU.
+    ///
+    /// The item, killstreak tier, australium, skin and crate series are read from the
+    /// `market_hash_name`, narrowed by the defindex and quality in `app_data` when given. The
+    /// effect, spells, strange parts, paint, sheen, killstreaker and craftability are read from
+    /// the text lines, and the wear from the "Exterior" tag. Craft numbers aren't part of
+    /// descriptions and are left unset.
+    ///
+    /// Names are matched as the schema gives them. A schema built by
+    /// [`Schema::from_items_game`](crate::Schema::from_items_game) names its items and effects by
+    /// localization tokens, so localize it first or use
+    /// [`SKU::from_description_with_localization`].
+    ///
+    /// # Errors
+    /// Returns [`ItemError::Name`] if the description has no defindex and its name doesn't match
+    /// exactly one item, and [`ItemError::UnknownQuality`] if the quality in `app_data` isn't
+    /// recognized.
+    pub fn from_description<S: ItemSchema + ?Sized>(
+        description: &Description,
+        schema: &S,
+    ) -> Result<Self, ItemError> {
+        SKU::from_description_in(description, schema, &[&English])
+    }
+
+    /// Converts an item description into a SKU, with item and effect names that are localization
+    /// tokens resolved from `locale`. Descriptions are in English, so this is usually
+    /// `tf_english.txt`.
+    ///
+    /// # Errors
+    /// The same as [`SKU::from_description`].
+    pub fn from_description_with_localization<S: ItemSchema + ?Sized>(
+        description: &Description,
+        schema: &S,
+        locale: &Localization,
+    ) -> Result<Self, ItemError> {
+        SKU::from_description_in(description, schema, &[locale, &English])
+    }
+
+    /// Converts an item description into a SKU using the words from any of `vocabularies`. Names
+    /// from the schema are resolved with the first.
+    fn from_description_in<S: ItemSchema + ?Sized>(
+        description: &Description,
+        schema: &S,
+        vocabularies: &[&dyn Vocabulary],
+    ) -> Result<Self, ItemError> {
+        let app_data = description.app_data.unwrap_or_default();
+        let quality = match app_data.quality {
+            Some(quality) => Some(Quality::try_from(quality)
+                .map_err(|_| ItemError::UnknownQuality(quality))?),
+            None => description.tag("Quality").and_then(enum_by_name::<Quality>),
+        };
+        let candidates = SKU::from_market_hash_name_in(&description.market_hash_name, schema, vocabularies);
+        let mut sku = match (app_data.def_index, candidates) {
+            (Some(def_index), candidates) => {
+                let defindex = i32::try_from(def_index)
+                    .map_err(|_| ItemError::InvalidDefindex(def_index))?;
+                let candidates = candidates.unwrap_or_default();
+
+                // Items sharing a name, such as the class variants of a weapon, are told apart
+                // by the defindex.
+                match candidates.iter().find(|sku| sku.defindex == defindex).or(candidates.first()) {
+                    Some(sku) => SKU {
+                        defindex,
+                        ..*sku
+                    },
+                    None => SKU::new(defindex, quality.unwrap_or(Quality::Unique)),
+                }
+            },
+            (None, Ok(mut candidates)) if candidates.len() == 1 => candidates.remove(0),
+            (None, Ok(candidates)) => return Err(NameParseError::Ambiguous(candidates).into()),
+            (None, Err(error)) => return Err(error.into()),
+        };
+
+        if let Some(quality) = quality {
+            // Market names put "Strange" first for elevated items, so it can be read as the
+            // quality of an item whose actual quality is something else.
+            if sku.quality == Quality::Strange && quality != Quality::Strange {
+                sku.strange = true;
+            }
+
+            sku.quality = quality;
+        }
+
+        if sku.quality == Quality::Strange {
+            sku.strange = false;
+        }
+
+        if sku.wear.is_none() {
+            sku.wear = description.tag("Exterior").and_then(enum_by_name::<Wear>);
+        }
+
+        for line in &description.descriptions {
+            apply_line(&mut sku, line.value.trim(), schema, vocabularies[0]);
+        }
+
+        Ok(sku)
+    }
+}
+
+/// Sets the part of a SKU held by a description line, if any. Effect names from the schema are
+/// resolved with `vocabulary`, and effects not in the schema are skipped.
+fn apply_line<S: ItemSchema + ?Sized>(
+    sku: &mut SKU,
+    line: &str,
+    schema: &S,
+    vocabulary: &dyn Vocabulary,
+) {
+    if let Some(effect) = line.strip_prefix("★ Unusual Effect: ") {
+        if let Some((particle, _)) = schema
+            .particles()
+            .find(|(_, name)| vocabulary.resolve(name).eq_ignore_ascii_case(effect))
+        {
+            sku.particle = Some(particle);
+        }
+    } else if let Some(spell) = line.strip_prefix("Halloween: ") {
+        let spell = spell
+            .strip_suffix("(spell only active during event)")
+            .unwrap_or(spell)
+            .trim();
+
+        if let Some(spell) = enum_by_name::<Spell>(spell) {
+            sku.spells.insert(spell);
+        }
+    } else if let Some(killstreaker) = line.strip_prefix("Killstreaker: ") {
+        sku.killstreaker = enum_by_name(killstreaker);
+    } else if let Some(sheen) = line.strip_prefix("Sheen: ") {
+        sku.sheen = enum_by_name(sheen);
+    } else if let Some(paint) = line.strip_prefix("Paint Color: ") {
+        sku.paint = enum_by_name::<Paint>(paint);
+    } else if let Some(crate_number) = line.strip_prefix("Crate Series #") {
+        sku.crate_number = crate_number.parse().ok().or(sku.crate_number);
+    } else if let Some(inner) = line.strip_prefix('(').and_then(|line| line.strip_suffix(')')) {
+        let inner = inner.trim();
+
+        // e.g. "( Not Usable in Crafting )" or "( Not Tradable, Marketable, or Usable in
+        // Crafting )".
+        if inner.starts_with("Not ") && inner.ends_with(NOT_USABLE_IN_CRAFTING) {
+            sku.craftable = false;
+        // e.g. "(Dominations: 0)".
+        } else if let Some(strange_part) = inner
+            .rsplit_once(':')
+            .and_then(|(name, _count)| enum_by_name::<StrangePart>(name.trim()))
+        {
+            sku.strange_parts.insert(strange_part);
+        }
+    }
+}
+
+/// Finds the description of an asset.
+fn find_description<'a>(descriptions: &'a [Description], asset: &Asset) -> Option<&'a Description> {
+    descriptions
+        .iter()
+        .find(|description| {
+            description.classid == asset.classid &&
+            description.instanceid == asset.instanceid
+        })
+}
+
+/// Finds an enum by its display name, ignoring case.
+fn enum_by_name<T: IntoEnumIterator + fmt::Display>(name: &str) -> Option<T> {
+    T::iter().find(|value| value.to_string().eq_ignore_ascii_case(name))
+}
+
+/// A number which Steam sometimes sends as a string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u32),
+    String(String),
+}
+
+impl NumberOrString {
+    fn into_number<E: serde::de::Error>(self) -> Result<u32, E> {
+        match self {
+            NumberOrString::Number(number) => Ok(number),
+            NumberOrString::String(string) => u32::from_str(&string).map_err(E::custom),
+        }
+    }
+}
+
+/// A flag which Steam sends as either a boolean or 0 and 1.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BoolOrNumber {
+    Bool(bool),
+    Number(u32),
+}
+
+fn deserialize_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    NumberOrString::deserialize(deserializer)?.into_number()
+}
+
+fn deserialize_option_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    Option::<NumberOrString>::deserialize(deserializer)?
+        .map(NumberOrString::into_number)
+        .transpose()
+}
+
+fn deserialize_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    Ok(match BoolOrNumber::deserialize(deserializer)? {
+        BoolOrNumber::Bool(value) => value,
+        BoolOrNumber::Number(value) => value != 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn inventory() -> Inventory {
+        let json = std::fs::read_to_string("tests/fixtures/community_inventory.json").unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn deserializes_inventory() {
+        let inventory = inventory();
+        let (asset, description) = inventory.items().next().unwrap();
+
+        assert!(inventory.success);
+        assert!(inventory.more_items);
+        assert_eq!(asset.amount, 1);
+        assert_eq!(inventory.items().count(), inventory.assets.len());
+        assert!(description.tradable);
+        assert_eq!(description.app_data.unwrap().def_index, Some(205));
+        assert_eq!(description.tag("Quality"), Some("Strange"));
+    }
+
+    #[test]
+    fn converts_descriptions() {
+        let inventory = inventory();
+        let skus = inventory
+            .items()
+            .map(|(_asset, description)| SKU::from_description(description, &schema()).unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(skus, [
+            "205;11;kt-3;ks-1;ke-2008;sp-28",
+            "378;5;u13;uncraftable;strange;p8421376;footprints-2;voices",
+            "16102;15;w3;pk102",
+            "5041;6;c2",
+            "10;6",
+            "6522;6;td-205",
+        ]);
+    }
+
+    #[test]
+    fn resolves_effects_with_a_localization() {
+        let schema = crate::Schema::load_items_game("tests/fixtures/items_game.txt").unwrap();
+        let mut locale = Localization::default();
+        let inventory = inventory();
+        let (_asset, description) = inventory.items().nth(1).unwrap();
+        let mut description = description.clone();
+
+        locale.insert("TF_SoldierHat1", "Team Captain");
+        locale.insert("Attrib_Particle13", "Burning Flames");
+        // Effects not in the schema don't replace the one already read.
+        description.descriptions.push(DescriptionLine {
+            kind: None,
+            value: "★ Unusual Effect: Unknown Effect".into(),
+            color: None,
+        });
+
+        assert_eq!(
+            SKU::from_description_with_localization(&description, &schema, &locale).unwrap().to_string(),
+            "378;5;u13;uncraftable;strange;p8421376;footprints-2;voices",
+        );
+        assert_eq!(SKU::from_description(&description, &schema).unwrap().particle, None);
+    }
+
+    #[test]
+    fn converts_trade_offer_descriptions() {
+        let json = std::fs::read_to_string("tests/fixtures/trade_offers.json").unwrap();
+        let response = serde_json::from_str::<GetTradeOffersResponse>(&json).unwrap();
+        let offer = &response.response.trade_offers_received[0];
+        let skus = offer.items_to_receive
+            .iter()
+            .map(|asset| {
+                let description = response.response.description(asset).unwrap();
+
+                SKU::from_description(description, &schema()).unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(offer.items_to_give[0].amount, 2);
+        assert_eq!(skus, ["200;6;festive", "378;6;uncraftable"]);
+    }
+
+    #[test]
+    fn requires_a_single_item_without_a_defindex() {
+        let description = Description {
+            market_hash_name: "Chemistry Set".into(),
+            ..Description::default()
+        };
+
+        assert!(matches!(
+            SKU::from_description(&description, &schema()),
+            Err(ItemError::Name(NameParseError::Ambiguous(candidates))) if candidates.len() == 2,
+        ));
+
+        let description = Description {
+            market_hash_name: "Unknown Hat".into(),
+            ..Description::default()
+        };
+
+        assert!(matches!(SKU::from_description(&description, &schema()), Err(ItemError::Name(_))));
+    }
+}
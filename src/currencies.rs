@@ -0,0 +1,365 @@
+//! Prices in keys and metal.
+
+use crate::SKU;
+use crate::error::CurrenciesParseError;
+use serde::de::{self, Deserializer};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use tf2_enum::Quality;
+
+/// The SKU of a Mann Co. Supply Crate Key.
+pub const KEY_SKU: &str = "5021;6";
+/// The SKU of a Refined Metal.
+pub const REFINED_SKU: &str = "5002;6";
+/// The SKU of a Reclaimed Metal.
+pub const RECLAIMED_SKU: &str = "5001;6";
+/// The SKU of a Scrap Metal.
+pub const SCRAP_SKU: &str = "5000;6";
+
+/// An amount of keys and metal.
+///
+/// Metal is counted in weapons, half of a scrap, so amounts such as "1.33 ref" are exact. It is
+/// (de)serialized as `{ "keys": 1, "metal": 1.33 }`, with metal in refined, and written as
+/// "1 key, 1.33 ref".
+///
+/// Arithmetic adds and subtracts keys and metal separately, never trading one for the other. Use
+/// [`Currencies::to_metal`] and [`Currencies::from_metal`] to convert at a key price. Ordering
+/// compares keys first, then metal.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::currencies::Currencies;
+///
+/// let currencies = serde_json::from_str::<Currencies>(r#"{ "keys": 1, "metal": 1.33 }"#).unwrap();
+///
+/// assert_eq!(currencies, Currencies::new(1, Currencies::ONE_REF + 3 * Currencies::ONE_SCRAP));
+/// assert_eq!(currencies.to_string(), "1 key, 1.33 ref");
+/// assert_eq!("1 key, 1.33 ref".parse::<Currencies>().unwrap(), currencies);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currencies {
+    /// The number of keys.
+    pub keys: i64,
+    /// The amount of metal, in weapons.
+    pub metal: i64,
+}
+
+impl Currencies {
+    /// The value of a weapon in weapons.
+    pub const ONE_WEAPON: i64 = 1;
+    /// The value of a scrap metal in weapons.
+    pub const ONE_SCRAP: i64 = 2;
+    /// The value of a reclaimed metal in weapons.
+    pub const ONE_REC: i64 = 6;
+    /// The value of a refined metal in weapons.
+    pub const ONE_REF: i64 = 18;
+
+    /// Creates currencies from keys and metal in weapons.
+    pub fn new(keys: i64, metal: i64) -> Self {
+        Self {
+            keys,
+            metal,
+        }
+    }
+
+    /// Converts an amount of refined e.g. 1.33 into weapons, rounding to the nearest weapon.
+    pub fn refined_to_weapons(refined: f64) -> i64 {
+        (refined * Self::ONE_REF as f64).round() as i64
+    }
+
+    /// Converts an amount of weapons into refined, truncated to two decimals the way TF2 prices
+    /// are written e.g. 1.33 rather than 1.3333.
+    pub fn weapons_to_refined(weapons: i64) -> f64 {
+        let hundredths = weapons.abs() * 100 / Self::ONE_REF;
+
+        weapons.signum() as f64 * hundredths as f64 / 100.0
+    }
+
+    /// Gets the value of a key, refined, reclaimed or scrap SKU. Returns `None` for any other SKU,
+    /// including uncraftable metal.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    /// use tf2_sku::currencies::{Currencies, RECLAIMED_SKU};
+    ///
+    /// let sku = RECLAIMED_SKU.parse::<SKU>().unwrap();
+    ///
+    /// assert_eq!(Currencies::from_sku(&sku), Some(Currencies::new(0, Currencies::ONE_REC)));
+    /// ```
+    pub fn from_sku(sku: &SKU) -> Option<Self> {
+        if *sku != SKU::new(sku.defindex, Quality::Unique) {
+            return None;
+        }
+
+        match sku.defindex {
+            5021 => Some(Self::new(1, 0)),
+            5002 => Some(Self::new(0, Self::ONE_REF)),
+            5001 => Some(Self::new(0, Self::ONE_REC)),
+            5000 => Some(Self::new(0, Self::ONE_SCRAP)),
+            _ => None,
+        }
+    }
+
+    /// Gets the total value in weapons, with keys worth `key_price` weapons each.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::currencies::Currencies;
+    ///
+    /// assert_eq!(Currencies::new(2, 6).to_metal(1086), 2178);
+    /// ```
+    pub fn to_metal(&self, key_price: i64) -> i64 {
+        self.keys * key_price + self.metal
+    }
+
+    /// Converts an amount of weapons into as many keys as it can buy at `key_price` weapons
+    /// each, with the rest in metal. A negative amount gives negative keys and metal.
+    ///
+    /// # Panics
+    /// Panics if `key_price` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::currencies::Currencies;
+    ///
+    /// assert_eq!(Currencies::from_metal(2178, 1086), Currencies::new(2, 6));
+    /// ```
+    pub fn from_metal(weapons: i64, key_price: i64) -> Self {
+        Self::new(weapons / key_price, weapons % key_price)
+    }
+
+    /// Trades metal for keys, or keys for metal, so the metal is less than a key at `key_price`
+    /// weapons each and has the same sign as the keys.
+    ///
+    /// # Panics
+    /// Panics if `key_price` is zero.
+    pub fn normalize(&self, key_price: i64) -> Self {
+        Self::from_metal(self.to_metal(key_price), key_price)
+    }
+}
+
+impl Add for Currencies {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.keys + other.keys, self.metal + other.metal)
+    }
+}
+
+impl Sub for Currencies {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.keys - other.keys, self.metal - other.metal)
+    }
+}
+
+impl Mul<i64> for Currencies {
+    type Output = Self;
+
+    fn mul(self, amount: i64) -> Self {
+        Self::new(self.keys * amount, self.metal * amount)
+    }
+}
+
+impl Neg for Currencies {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.keys, -self.metal)
+    }
+}
+
+impl AddAssign for Currencies {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Currencies {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Sum for Currencies {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Currencies> for Currencies {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for Currencies {
+    /// Writes the currencies e.g. "2 keys, 14.33 ref". Zero keys or metal are left out unless
+    /// both are zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let has_keys = self.keys != 0 || self.metal == 0;
+
+        if has_keys {
+            let unit = if self.keys.abs() == 1 { "key" } else { "keys" };
+
+            write!(f, "{} {unit}", self.keys)?;
+        }
+
+        if self.metal != 0 || self.keys == 0 {
+            if has_keys {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{} ref", Self::weapons_to_refined(self.metal))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Currencies {
+    type Err = CurrenciesParseError;
+
+    /// Parses currencies written like "2 keys, 14.33 ref", "1 key" or "0.11 ref".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(CurrenciesParseError::Empty);
+        }
+
+        let mut currencies = Self::default();
+
+        for part in s.split(',') {
+            let invalid = || CurrenciesParseError::InvalidPart(part.trim().to_string());
+            let (amount, unit) = part.trim().split_once(' ').ok_or_else(invalid)?;
+            let amount = amount.parse::<f64>().map_err(|_| invalid())?;
+
+            match unit.trim() {
+                "key" | "keys" => {
+                    if amount.fract() != 0.0 {
+                        return Err(CurrenciesParseError::FractionalKeys(part.trim().to_string()));
+                    }
+
+                    currencies.keys += amount as i64;
+                },
+                "ref" => currencies.metal += Self::refined_to_weapons(amount),
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(currencies)
+    }
+}
+
+impl Serialize for Currencies {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Currencies", 2)?;
+
+        state.serialize_field("keys", &self.keys)?;
+
+        // Whole amounts are written without a fraction e.g. 2 rather than 2.0.
+        if self.metal % Self::ONE_REF == 0 {
+            state.serialize_field("metal", &(self.metal / Self::ONE_REF))?;
+        } else {
+            state.serialize_field("metal", &Self::weapons_to_refined(self.metal))?;
+        }
+
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Currencies {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Refined {
+            #[serde(default)]
+            keys: f64,
+            #[serde(default)]
+            metal: f64,
+        }
+
+        let refined = Refined::deserialize(deserializer)?;
+
+        if refined.keys.fract() != 0.0 {
+            return Err(de::Error::custom(format!("keys must be a whole number: {}", refined.keys)));
+        }
+
+        Ok(Self {
+            keys: refined.keys as i64,
+            metal: Self::refined_to_weapons(refined.metal),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_refined() {
+        for (refined, weapons) in [(1.33, 24), (1.66, 30), (0.05, 1), (0.11, 2), (1.55, 28), (-1.33, -24), (60.44, 1088)] {
+            assert_eq!(Currencies::refined_to_weapons(refined), weapons, "{refined}");
+            assert_eq!(Currencies::weapons_to_refined(weapons), refined, "{weapons}");
+        }
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let currencies = Currencies::new(2, 42);
+        let json = serde_json::to_string(&currencies).unwrap();
+
+        assert_eq!(json, r#"{"keys":2,"metal":2.33}"#);
+        assert_eq!(serde_json::from_str::<Currencies>(&json).unwrap(), currencies);
+        assert_eq!(serde_json::from_str::<Currencies>(r#"{"metal":0.11}"#).unwrap(), Currencies::new(0, 2));
+        assert_eq!(serde_json::to_string(&Currencies::new(1, 36)).unwrap(), r#"{"keys":1,"metal":2}"#);
+        assert!(serde_json::from_str::<Currencies>(r#"{"keys":1.5}"#).is_err());
+    }
+
+    #[test]
+    fn formats_and_parses() {
+        for (currencies, string) in [
+            (Currencies::new(2, 258), "2 keys, 14.33 ref"),
+            (Currencies::new(1, 0), "1 key"),
+            (Currencies::new(0, 30), "1.66 ref"),
+            (Currencies::new(0, 0), "0 keys, 0 ref"),
+            (Currencies::new(-1, -2), "-1 key, -0.11 ref"),
+        ] {
+            assert_eq!(currencies.to_string(), string);
+            assert_eq!(string.parse::<Currencies>(), Ok(currencies));
+        }
+
+        assert_eq!("".parse::<Currencies>(), Err(CurrenciesParseError::Empty));
+        assert_eq!("2 keys, 3 rec".parse::<Currencies>(), Err(CurrenciesParseError::InvalidPart("3 rec".into())));
+        assert_eq!("1.5 keys".parse::<Currencies>(), Err(CurrenciesParseError::FractionalKeys("1.5 keys".into())));
+    }
+
+    #[test]
+    fn adds_and_converts_exactly() {
+        let scrap = Currencies::new(0, Currencies::ONE_SCRAP);
+        let total = (0..9).map(|_| scrap).sum::<Currencies>();
+
+        // Nine scrap is a refined, where 9 * 0.11 is not 1.0.
+        assert_eq!(total, Currencies::new(0, Currencies::ONE_REF));
+        assert_eq!(Currencies::new(1, 12) - Currencies::new(0, 30), Currencies::new(1, -18));
+        assert_eq!((Currencies::new(1, -18)).normalize(1086), Currencies::new(0, 1068));
+        assert_eq!(Currencies::new(0, 2178).normalize(1086), Currencies::new(2, 6));
+        assert_eq!(Currencies::new(1, 5) * 3, Currencies::new(3, 15));
+        assert!(Currencies::new(1, 0) > Currencies::new(0, 2000));
+    }
+
+    #[test]
+    fn values_currency_skus() {
+        let value = |sku: &str| Currencies::from_sku(&sku.parse().unwrap());
+
+        assert_eq!(value(KEY_SKU), Some(Currencies::new(1, 0)));
+        assert_eq!(value(REFINED_SKU), Some(Currencies::new(0, 18)));
+        assert_eq!(value(SCRAP_SKU), Some(Currencies::new(0, 2)));
+        assert_eq!(value("5002;6;uncraftable"), None);
+        assert_eq!(value("205;6"), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Summaries of the attributes that aren't part of an item's name, for listing descriptions.
+
+use crate::SKU;
+
+/// An attribute included in a description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Detail {
+    /// e.g. "Spells: Headless Horseshoes, Voices from Below".
+    Spells,
+    /// e.g. "Parts: Kills, Dominations".
+    StrangeParts,
+    /// e.g. "Paint: Drably Olive".
+    Paint,
+    /// e.g. "Sheen: Team Shine".
+    Sheen,
+    /// e.g. "Killstreaker: Hypno-Beam".
+    Killstreaker,
+    /// e.g. "Craft #100".
+    CraftNumber,
+    /// "Festivized".
+    Festivized,
+}
+
+/// Options for [`SKU::describe`].
+///
+/// # Examples
+/// ```
+/// use tf2_sku::describe::{DescribeOptions, Detail};
+///
+/// let options = DescribeOptions {
+///     order: vec![Detail::Paint, Detail::Spells],
+///     separator: ", ".into(),
+///     max_length: Some(80),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescribeOptions {
+    /// The details to include, in the order they are written.
+    pub order: Vec<Detail>,
+    /// The details from highest to lowest priority. When the description is over `max_length`,
+    /// the lowest priority details are dropped first. Details missing from this list have the
+    /// lowest priority.
+    pub priority: Vec<Detail>,
+    /// The separator between details.
+    pub separator: String,
+    /// The separator between items in a list e.g. between spells.
+    pub list_separator: String,
+    /// The maximum length of the description in characters.
+    pub max_length: Option<usize>,
+}
+
+impl Default for DescribeOptions {
+    /// Includes every detail separated by " | ", with no length limit.
+    fn default() -> Self {
+        Self {
+            order: vec![
+                Detail::Spells,
+                Detail::StrangeParts,
+                Detail::Paint,
+                Detail::Sheen,
+                Detail::Killstreaker,
+                Detail::CraftNumber,
+                Detail::Festivized,
+            ],
+            priority: vec![
+                Detail::Spells,
+                Detail::Paint,
+                Detail::StrangeParts,
+                Detail::Killstreaker,
+                Detail::Sheen,
+                Detail::Festivized,
+                Detail::CraftNumber,
+            ],
+            separator: " | ".into(),
+            list_separator: ", ".into(),
+            max_length: None,
+        }
+    }
+}
+
+impl SKU {
+    /// Describes the attributes of this SKU that aren't part of its name, for listing
+    /// descriptions. Returns an empty string if there is nothing to describe.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    /// use tf2_sku::describe::DescribeOptions;
+    ///
+    /// let sku = "205;11;footprints-2;voices;sp-28;p8421376;ks-1".parse::<SKU>().unwrap();
+    ///
+    /// assert_eq!(
+    ///     sku.describe(&DescribeOptions::default()),
+    ///     "Spells: Headless Horseshoes, Voices from Below | Parts: Dominations | Paint: Drably Olive | Sheen: Team Shine",
+    /// );
+    /// ```
+    pub fn describe(&self, options: &DescribeOptions) -> String {
+        let mut details = options.order
+            .iter()
+            .filter_map(|detail| Some((*detail, self.detail(*detail, &options.list_separator)?)))
+            .collect::<Vec<_>>();
+
+        if let Some(max_length) = options.max_length {
+            let rank = |detail: Detail| options.priority
+                .iter()
+                .position(|prioritized| *prioritized == detail)
+                .unwrap_or(usize::MAX);
+            let separator_length = options.separator.chars().count();
+
+            while !details.is_empty() {
+                let length = details
+                    .iter()
+                    .map(|(_, text)| text.chars().count())
+                    .sum::<usize>() + separator_length * (details.len() - 1);
+
+                if length <= max_length {
+                    break;
+                }
+
+                // Later details of the same priority are dropped first.
+                let lowest = details
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, (detail, _))| rank(*detail))
+                    .map(|(index, _)| index)
+                    .unwrap_or_default();
+
+                details.remove(lowest);
+            }
+        }
+
+        details
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect::<Vec<_>>()
+            .join(&options.separator)
+    }
+
+    fn detail(&self, detail: Detail, list_separator: &str) -> Option<String> {
+        match detail {
+            Detail::Spells => {
+                let spells = self.spells
+                    .into_iter()
+                    .map(|spell| spell.to_string())
+                    .collect::<Vec<_>>();
+
+                (!spells.is_empty()).then(|| format!("Spells: {}", spells.join(list_separator)))
+            },
+            Detail::StrangeParts => {
+                let strange_parts = self.strange_parts
+                    .into_iter()
+                    .map(|strange_part| strange_part.to_string())
+                    .collect::<Vec<_>>();
+
+                (!strange_parts.is_empty())
+                    .then(|| format!("Parts: {}", strange_parts.join(list_separator)))
+            },
+            Detail::Paint => self.paint.map(|paint| format!("Paint: {paint}")),
+            Detail::Sheen => self.sheen.map(|sheen| format!("Sheen: {sheen}")),
+            Detail::Killstreaker => self.killstreaker
+                .map(|killstreaker| format!("Killstreaker: {killstreaker}")),
+            Detail::CraftNumber => self.craft_number
+                .map(|craft_number| format!("Craft #{craft_number}")),
+            Detail::Festivized => self.festivized.then(|| "Festivized".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sku() -> SKU {
+        "205;11;kt-3;ks-1;ke-2008;festive;n100;footprints-2;voices;sp-28;p8421376"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn describes_every_detail() {
+        assert_eq!(
+            sku().describe(&DescribeOptions::default()),
+            "Spells: Headless Horseshoes, Voices from Below | Parts: Dominations | Paint: Drably Olive | Sheen: Team Shine | Killstreaker: Hypno-Beam | Craft #100 | Festivized",
+        );
+        assert_eq!("205;11".parse::<SKU>().unwrap().describe(&DescribeOptions::default()), "");
+    }
+
+    #[test]
+    fn uses_order_and_separators() {
+        let options = DescribeOptions {
+            order: vec![Detail::Paint, Detail::Spells, Detail::CraftNumber],
+            separator: "; ".into(),
+            list_separator: " & ".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            sku().describe(&options),
+            "Paint: Drably Olive; Spells: Headless Horseshoes & Voices from Below; Craft #100",
+        );
+    }
+
+    #[test]
+    fn drops_low_priority_details_over_budget() {
+        let options = DescribeOptions {
+            max_length: Some(70),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            sku().describe(&options),
+            "Spells: Headless Horseshoes, Voices from Below | Paint: Drably Olive",
+        );
+
+        let options = DescribeOptions {
+            max_length: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(sku().describe(&options), "");
+    }
+}
@@ -1,7 +1,9 @@
 //! Errors.
 
 use std::fmt;
+use std::io;
 use std::num::{IntErrorKind, ParseIntError};
+use std::path::PathBuf;
 
 /// An error when parsing from a string.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -49,3 +51,415 @@ impl fmt::Display for ParseError {
 }
 
 impl std::error::Error for ParseError {}
+
+/// An error when parsing or loading a KeyValues document.
+#[derive(Debug)]
+pub enum KeyValuesError {
+    /// The document ended unexpectedly e.g. an unclosed block or string.
+    UnexpectedEof,
+    /// The document is malformed.
+    Syntax {
+        /// The line the error occurred on.
+        line: usize,
+        /// A description of the error.
+        message: &'static str,
+    },
+    /// A `#base` or `#include` directive was found in a document that was not loaded from a file.
+    UnresolvedDirective {
+        /// The path of the directive.
+        path: String,
+    },
+    /// A `#base` or `#include` directive refers to a file that is already being loaded.
+    DirectiveCycle {
+        /// The path of the file.
+        path: PathBuf,
+    },
+    /// A referenced prefab does not exist.
+    UnknownPrefab {
+        /// The name of the prefab.
+        name: String,
+    },
+    /// Prefabs reference each other in a cycle.
+    PrefabCycle {
+        /// The name of the prefab where the cycle was detected.
+        name: String,
+    },
+    /// The file is not valid UTF-8 or UTF-16.
+    InvalidEncoding {
+        /// The path of the file.
+        path: PathBuf,
+    },
+    /// The file could not be read.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+        /// The error from reading the file.
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for KeyValuesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyValuesError::UnexpectedEof => write!(f, "Unexpected end of KeyValues document."),
+            KeyValuesError::Syntax {
+                line,
+                message,
+            } => write!(f, "KeyValues syntax error on line {line}: {message}"),
+            KeyValuesError::UnresolvedDirective {
+                path,
+            } => write!(f, "Cannot resolve directive for \"{path}\" without a file path."),
+            KeyValuesError::DirectiveCycle {
+                path,
+            } => write!(f, "Directive cycle detected at {}", path.display()),
+            KeyValuesError::UnknownPrefab {
+                name,
+            } => write!(f, "Unknown prefab: {name}"),
+            KeyValuesError::PrefabCycle {
+                name,
+            } => write!(f, "Prefab cycle detected at {name}"),
+            KeyValuesError::InvalidEncoding {
+                path,
+            } => write!(f, "{} is not valid UTF-8 or UTF-16.", path.display()),
+            KeyValuesError::Io {
+                path,
+                error,
+            } => write!(f, "Failed to read {}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for KeyValuesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KeyValuesError::Io {
+                error,
+                ..
+            } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// An error when building a schema.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The KeyValues document could not be parsed.
+    KeyValues(KeyValuesError),
+    /// A required block is missing from the document.
+    MissingBlock(&'static str),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::KeyValues(error) => write!(f, "{error}"),
+            SchemaError::MissingBlock(name) => write!(f, "Schema is missing the \"{name}\" block."),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SchemaError::KeyValues(error) => Some(error),
+            SchemaError::MissingBlock(_) => None,
+        }
+    }
+}
+
+impl From<KeyValuesError> for SchemaError {
+    fn from(error: KeyValuesError) -> Self {
+        SchemaError::KeyValues(error)
+    }
+}
+
+/// An error when checking a SKU against a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The defindex is not in the schema.
+    UnknownItem(i32),
+    /// The particle is not in the schema.
+    UnknownParticle(u32),
+    /// The paint kit (skin) is not in the schema.
+    UnknownPaintKit(u32),
+    /// The target defindex is not in the schema.
+    UnknownTarget(u32),
+    /// The output defindex is not in the schema.
+    UnknownOutput(u32),
+    /// The item has a paint but cannot be painted.
+    NotPaintable,
+    /// The item has a killstreak tier but cannot be killstreakified.
+    NotKillstreakable,
+    /// The crate number does not match the crate's series.
+    CrateSeriesMismatch {
+        /// The series defined by the schema.
+        expected: u32,
+        /// The series in the SKU.
+        found: u32,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UnknownItem(defindex) => write!(f, "Unknown item: {defindex}"),
+            ValidationError::UnknownParticle(particle) => write!(f, "Unknown particle: {particle}"),
+            ValidationError::UnknownPaintKit(skin) => write!(f, "Unknown paint kit: {skin}"),
+            ValidationError::UnknownTarget(defindex) => write!(f, "Unknown target item: {defindex}"),
+            ValidationError::UnknownOutput(defindex) => write!(f, "Unknown output item: {defindex}"),
+            ValidationError::NotPaintable => write!(f, "Item cannot be painted."),
+            ValidationError::NotKillstreakable => write!(f, "Item cannot be killstreakified."),
+            ValidationError::CrateSeriesMismatch {
+                expected,
+                found,
+            } => write!(f, "Crate series #{found} does not match schema series #{expected}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// An error when parsing an item name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameParseError {
+    /// The name is empty.
+    Empty,
+    /// No item in the schema matches the name. Contains the part of the name left unrecognized.
+    UnknownItem(String),
+    /// The name matches more than one item. Contains every matching SKU.
+    Ambiguous(Vec<crate::SKU>),
+}
+
+impl fmt::Display for NameParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameParseError::Empty => write!(f, "Name is empty."),
+            NameParseError::UnknownItem(name) => write!(f, "Unknown item: {name}"),
+            NameParseError::Ambiguous(candidates) => write!(f, "Name matches {} items.", candidates.len()),
+        }
+    }
+}
+
+impl std::error::Error for NameParseError {}
+
+/// An error when parsing a URL into a SKU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlError {
+    /// The URL is not a link to an item on a supported site.
+    Unsupported(String),
+    /// The URL is missing a part needed to identify the item.
+    MissingPart(&'static str),
+    /// The quality in the URL is not recognized.
+    UnknownQuality(String),
+    /// The price index in the URL is not valid.
+    InvalidPriceIndex(String),
+    /// The SKU embedded in the URL could not be parsed.
+    Sku(ParseError),
+    /// The item name in the URL could not be parsed.
+    Name(NameParseError),
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlError::Unsupported(url) => write!(f, "Unsupported URL: {url}"),
+            UrlError::MissingPart(part) => write!(f, "URL is missing the {part}."),
+            UrlError::UnknownQuality(quality) => write!(f, "Unknown quality: {quality}"),
+            UrlError::InvalidPriceIndex(price_index) => write!(f, "Invalid price index: {price_index}"),
+            UrlError::Sku(error) => write!(f, "{error}"),
+            UrlError::Name(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for UrlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UrlError::Sku(error) => Some(error),
+            UrlError::Name(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for UrlError {
+    fn from(error: ParseError) -> Self {
+        UrlError::Sku(error)
+    }
+}
+
+impl From<NameParseError> for UrlError {
+    fn from(error: NameParseError) -> Self {
+        UrlError::Name(error)
+    }
+}
+
+/// An error when converting an item into a SKU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemError {
+    /// The defindex does not fit in a SKU.
+    InvalidDefindex(u32),
+    /// The quality is not recognized.
+    UnknownQuality(u32),
+    /// The item's name could not be parsed, and nothing else identifies the item.
+    Name(NameParseError),
+    /// The backpack.tf price index is not valid for the item.
+    InvalidPriceIndex(String),
+    /// The item is not tradable, which a SKU can't record.
+    NotTradable,
+    /// A field needed to make a SKU is missing from the item e.g. the defindex.
+    MissingField(&'static str),
+}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemError::InvalidDefindex(defindex) => write!(f, "Invalid defindex: {defindex}"),
+            ItemError::UnknownQuality(quality) => write!(f, "Unknown quality: {quality}"),
+            ItemError::Name(error) => write!(f, "{error}"),
+            ItemError::InvalidPriceIndex(price_index) => write!(f, "Invalid price index: {price_index}"),
+            ItemError::NotTradable => write!(f, "Item is not tradable."),
+            ItemError::MissingField(field) => write!(f, "Missing field: {field}"),
+        }
+    }
+}
+
+impl std::error::Error for ItemError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ItemError::Name(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<NameParseError> for ItemError {
+    fn from(error: NameParseError) -> Self {
+        ItemError::Name(error)
+    }
+}
+
+/// An error when parsing currencies from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CurrenciesParseError {
+    /// The string is empty.
+    Empty,
+    /// A part of the string is not an amount of keys or refined e.g. "2 keys" or "14.33 ref".
+    InvalidPart(String),
+    /// The number of keys is not a whole number.
+    FractionalKeys(String),
+}
+
+impl fmt::Display for CurrenciesParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurrenciesParseError::Empty => write!(f, "Currencies are empty."),
+            CurrenciesParseError::InvalidPart(part) => write!(f, "Invalid currencies: {part}"),
+            CurrenciesParseError::FractionalKeys(keys) => write!(f, "Keys must be a whole number: {keys}"),
+        }
+    }
+}
+
+impl std::error::Error for CurrenciesParseError {}
+
+/// An error when making change for an amount owed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeError {
+    /// The amount owed has negative keys or metal.
+    NegativeAmount(crate::currencies::Currencies),
+    /// The key price is zero or negative.
+    InvalidKeyPrice(i64),
+    /// Not enough keys are held, and there is no key price to pay for them in metal.
+    NotEnoughKeys {
+        /// The number of keys owed.
+        owed: i64,
+        /// The number of keys held.
+        held: i64,
+    },
+    /// Not enough metal is held, and keys can't make up for it.
+    NotEnoughMetal {
+        /// The metal owed, in weapons.
+        owed: i64,
+        /// The metal held, in weapons.
+        held: i64,
+    },
+    /// The metal held can't pay the amount exactly, and change isn't allowed.
+    NoExactChange {
+        /// The metal owed, in weapons.
+        owed: i64,
+    },
+}
+
+impl fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::currencies::Currencies;
+
+        match self {
+            ChangeError::NegativeAmount(amount) => write!(f, "Amount owed is negative: {amount}"),
+            ChangeError::InvalidKeyPrice(key_price) => write!(f, "Invalid key price: {key_price}"),
+            ChangeError::NotEnoughKeys {
+                owed,
+                held,
+            } => write!(f, "Not enough keys: {owed} owed, {held} held."),
+            ChangeError::NotEnoughMetal {
+                owed,
+                held,
+            } => write!(
+                f,
+                "Not enough metal: {} ref owed, {} ref held.",
+                Currencies::weapons_to_refined(*owed),
+                Currencies::weapons_to_refined(*held),
+            ),
+            ChangeError::NoExactChange {
+                owed,
+            } => write!(f, "No exact change for {} ref.", Currencies::weapons_to_refined(*owed)),
+        }
+    }
+}
+
+impl std::error::Error for ChangeError {}
+
+/// An error when decoding an item from a Game Coordinator message.
+#[cfg(feature = "gc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GcError {
+    /// The message could not be decoded.
+    Decode(prost::DecodeError),
+    /// The decoded item could not be converted into a SKU.
+    Item(ItemError),
+}
+
+#[cfg(feature = "gc")]
+impl fmt::Display for GcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GcError::Decode(error) => write!(f, "{error}"),
+            GcError::Item(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+#[cfg(feature = "gc")]
+impl std::error::Error for GcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GcError::Decode(error) => Some(error),
+            GcError::Item(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "gc")]
+impl From<prost::DecodeError> for GcError {
+    fn from(error: prost::DecodeError) -> Self {
+        GcError::Decode(error)
+    }
+}
+
+#[cfg(feature = "gc")]
+impl From<ItemError> for GcError {
+    fn from(error: ItemError) -> Self {
+        GcError::Item(error)
+    }
+}
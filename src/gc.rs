@@ -0,0 +1,240 @@
+//! Decoding of items from Game Coordinator (GC) `CSOEconItem` protobuf messages.
+//!
+//! The message definitions are bundled from TF2's `base_gcmessages.proto`, keeping only the
+//! messages of items. The kept messages are in `proto/base_gcmessages.proto`, which the structs
+//! here match field for field. Attribute values are sent either as the `value` field or as 4 little-endian
+//! bytes in `value_bytes`, and hold the bits of a float or an unsigned integer depending on the
+//! attribute. Both readings are kept when converting to [`ItemAttribute`], the same as the Steam
+//! Web API does, so each attribute is read the way it is stored.
+
+use crate::SKU;
+use crate::error::{GcError, ItemError};
+use prost::Message;
+use tf2_enum::{ItemAttribute, Quality};
+
+/// The flag set on items which can't be traded.
+pub const FLAG_CANNOT_TRADE: u32 = 1 << 0;
+/// The flag set on items which can't be used in crafting.
+pub const FLAG_CANNOT_CRAFT: u32 = 1 << 1;
+
+/// An item, as sent in the GC's shared object cache.
+#[derive(Clone, PartialEq, Message)]
+pub struct CsoEconItem {
+    /// The item's ID.
+    #[prost(uint64, optional, tag = "1")]
+    pub id: Option<u64>,
+    /// The 32-bit account ID of the owner.
+    #[prost(uint32, optional, tag = "2")]
+    pub account_id: Option<u32>,
+    /// The position in the backpack, and whether the item is new.
+    #[prost(uint32, optional, tag = "3")]
+    pub inventory: Option<u32>,
+    /// The defindex.
+    #[prost(uint32, optional, tag = "4")]
+    pub def_index: Option<u32>,
+    /// The quantity.
+    #[prost(uint32, optional, tag = "5")]
+    pub quantity: Option<u32>,
+    /// The level.
+    #[prost(uint32, optional, tag = "6")]
+    pub level: Option<u32>,
+    /// The quality.
+    #[prost(uint32, optional, tag = "7")]
+    pub quality: Option<u32>,
+    /// The flags e.g. [`FLAG_CANNOT_CRAFT`].
+    #[prost(uint32, optional, tag = "8")]
+    pub flags: Option<u32>,
+    /// How the item was obtained.
+    #[prost(uint32, optional, tag = "9")]
+    pub origin: Option<u32>,
+    /// The name given with a Name Tag.
+    #[prost(string, optional, tag = "10")]
+    pub custom_name: Option<String>,
+    /// The description given with a Description Tag.
+    #[prost(string, optional, tag = "11")]
+    pub custom_desc: Option<String>,
+    /// The attributes.
+    #[prost(message, repeated, tag = "12")]
+    pub attribute: Vec<CsoEconItemAttribute>,
+    /// The item inside this item e.g. the gift in a wrapped gift.
+    #[prost(message, optional, boxed, tag = "13")]
+    pub interior_item: Option<Box<CsoEconItem>>,
+    /// Whether the item is in use.
+    #[prost(bool, optional, tag = "14")]
+    pub in_use: Option<bool>,
+    /// The style.
+    #[prost(uint32, optional, tag = "15")]
+    pub style: Option<u32>,
+    /// The item's original ID, before it last changed e.g. from being painted.
+    #[prost(uint64, optional, tag = "16")]
+    pub original_id: Option<u64>,
+    /// Whether `equipped_state` is sent.
+    #[prost(bool, optional, tag = "17")]
+    pub contains_equipped_state: Option<bool>,
+    /// The classes and slots the item is equipped in.
+    #[prost(message, repeated, tag = "18")]
+    pub equipped_state: Vec<CsoEconItemEquipped>,
+    /// Whether `equipped_state` is sent in its second version.
+    #[prost(bool, optional, tag = "19")]
+    pub contains_equipped_state_v2: Option<bool>,
+}
+
+/// An attribute of an item.
+#[derive(Clone, PartialEq, Message)]
+pub struct CsoEconItemAttribute {
+    /// The defindex of the attribute.
+    #[prost(uint32, optional, tag = "1")]
+    pub def_index: Option<u32>,
+    /// The value, as the bits of a float or an unsigned integer.
+    #[prost(uint32, optional, tag = "2")]
+    pub value: Option<u32>,
+    /// The value as bytes. 4 bytes hold the same bits as `value`, little-endian; anything else is
+    /// a nested message e.g. a string.
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub value_bytes: Option<Vec<u8>>,
+}
+
+impl CsoEconItemAttribute {
+    /// Gets the value of an attribute stored as an unsigned integer e.g. the paint kit, which is
+    /// also the bits of a float value. Returns `None` for values which aren't 32 bits, such as
+    /// strings.
+    pub fn uint_value(&self) -> Option<u32> {
+        match &self.value_bytes {
+            Some(bytes) => <[u8; 4]>::try_from(bytes.as_slice())
+                .ok()
+                .map(u32::from_le_bytes),
+            None => self.value,
+        }
+    }
+
+    /// Gets the value of an attribute stored as a float e.g. the killstreak tier.
+    pub fn float_value(&self) -> Option<f32> {
+        self.uint_value().map(f32::from_bits)
+    }
+}
+
+/// A class and slot an item is equipped in.
+#[derive(Clone, Copy, PartialEq, Message)]
+pub struct CsoEconItemEquipped {
+    /// The class.
+    #[prost(uint32, optional, tag = "1")]
+    pub new_class: Option<u32>,
+    /// The slot.
+    #[prost(uint32, optional, tag = "2")]
+    pub new_slot: Option<u32>,
+}
+
+impl TryFrom<&CsoEconItem> for SKU {
+    type Error = ItemError;
+
+    /// Converts an item into a SKU from its attributes. Attributes which aren't 32 bits, such as
+    /// the recipe components of Chemistry Sets, are skipped.
+    ///
+    /// Returns [`ItemError::MissingField`] if the item has no `def_index` or `quality`, such as
+    /// when the message was cut short.
+    fn try_from(item: &CsoEconItem) -> Result<Self, Self::Error> {
+        let def_index = item.def_index.ok_or(ItemError::MissingField("def_index"))?;
+        let defindex = i32::try_from(def_index)
+            .map_err(|_| ItemError::InvalidDefindex(def_index))?;
+        let quality = item.quality.ok_or(ItemError::MissingField("quality"))?;
+        let quality = Quality::try_from(quality)
+            .map_err(|_| ItemError::UnknownQuality(quality))?;
+        let attributes = item.attribute
+            .iter()
+            .filter_map(item_attribute)
+            .collect::<Vec<_>>();
+        let mut sku = SKU::from_attributes(defindex, quality, &attributes);
+
+        sku.craftable = item.flags.unwrap_or_default() & FLAG_CANNOT_CRAFT == 0;
+
+        Ok(sku)
+    }
+}
+
+impl SKU {
+    /// Decodes an encoded `CSOEconItem` message into a SKU.
+    ///
+    /// # Errors
+    /// Returns [`GcError::Decode`] if the bytes aren't a valid message and [`GcError::Item`] if
+    /// the item can't be converted.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    ///
+    /// // def_index 5021, quality 6.
+    /// let bytes = [0x20, 0x9d, 0x27, 0x38, 0x06];
+    ///
+    /// assert_eq!(SKU::from_gc_item_bytes(&bytes).unwrap().to_string(), "5021;6");
+    /// ```
+    pub fn from_gc_item_bytes(bytes: &[u8]) -> Result<Self, GcError> {
+        let item = CsoEconItem::decode(bytes)?;
+
+        Ok(SKU::try_from(&item)?)
+    }
+}
+
+/// Converts an attribute holding 32 bits, keeping both the integer and float readings.
+fn item_attribute(attribute: &CsoEconItemAttribute) -> Option<ItemAttribute> {
+    let bits = attribute.uint_value()?;
+
+    Some(ItemAttribute {
+        defindex: attribute.def_index?,
+        value: bits.into(),
+        float_value: Some(f32::from_bits(bits)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        std::fs::read(format!("tests/fixtures/gc/{name}.bin")).unwrap()
+    }
+
+    #[test]
+    fn decodes_items() {
+        let item = CsoEconItem::decode(fixture("unusual_team_captain").as_slice()).unwrap();
+
+        assert_eq!(item.def_index, Some(378));
+        assert_eq!(item.custom_name.as_deref(), Some("Hat of Flames"));
+        assert_eq!(item.attribute.len(), 6);
+        assert_eq!(item.attribute[0].float_value(), Some(13.0));
+        assert_eq!(item.attribute[4].uint_value(), Some(100));
+        // A string, which isn't 32 bits.
+        assert_eq!(item.attribute[5].uint_value(), None);
+    }
+
+    #[test]
+    fn converts_items() {
+        let skus = ["strange_rocket_launcher", "unusual_team_captain", "war_paint"]
+            .map(|name| SKU::from_gc_item_bytes(&fixture(name)).unwrap().to_string());
+
+        assert_eq!(skus, [
+            "205;11;australium;kt-3;ks-1;ke-2008;sp-28",
+            "378;5;u13;uncraftable;strange;n100;p8421376;footprints-2",
+            "16102;15;w3;pk102",
+        ]);
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        assert!(matches!(SKU::from_gc_item_bytes(&[0x20]), Err(GcError::Decode(_))));
+
+        // def_index 5021, quality 100.
+        assert_eq!(
+            SKU::from_gc_item_bytes(&[0x20, 0x9d, 0x27, 0x38, 0x64]),
+            Err(GcError::Item(ItemError::UnknownQuality(100))),
+        );
+        // def_index 5021, with the quality cut off.
+        assert_eq!(
+            SKU::from_gc_item_bytes(&[0x20, 0x9d, 0x27]),
+            Err(GcError::Item(ItemError::MissingField("quality"))),
+        );
+        assert_eq!(
+            SKU::from_gc_item_bytes(&[]),
+            Err(GcError::Item(ItemError::MissingField("def_index"))),
+        );
+    }
+}
@@ -0,0 +1,699 @@
+//! Parser for Valve's KeyValues (VDF) text format, as used by `items_game.txt` and the
+//! localization files.
+//!
+//! ## Usage
+//! ```
+//! use tf2_sku::keyvalues::KeyValues;
+//!
+//! let kv = KeyValues::parse(r#"
+//!     "items_game"
+//!     {
+//!         "items"
+//!         {
+//!             "5021"
+//!             {
+//!                 "name"  "Decoder Ring"
+//!                 "item_class"    "tool"
+//!             }
+//!         }
+//!     }
+//! "#).unwrap();
+//! let item = kv.get_object("items_game")
+//!     .and_then(|items_game| items_game.get_object("items"))
+//!     .and_then(|items| items.get_object("5021"))
+//!     .unwrap();
+//!
+//! assert_eq!(item.get_str("name"), Some("Decoder Ring"));
+//! ```
+
+use crate::error::KeyValuesError;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefab chains deeper than this are treated as cyclic.
+const MAX_PREFAB_DEPTH: usize = 32;
+
+/// A value in a KeyValues document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// A string value.
+    String(String),
+    /// A nested block of key-value pairs.
+    Object(KeyValues),
+}
+
+impl Value {
+    /// Gets the value as a string, if it is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            Value::Object(_) => None,
+        }
+    }
+
+    /// Gets the value as an object, if it is one.
+    pub fn as_object(&self) -> Option<&KeyValues> {
+        match self {
+            Value::String(_) => None,
+            Value::Object(kv) => Some(kv),
+        }
+    }
+}
+
+/// An ordered block of key-value pairs. Keys are compared case-insensitively, as they are by the
+/// game. Duplicate keys are preserved in the order they appear.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyValues {
+    entries: Vec<(String, Value)>,
+}
+
+impl KeyValues {
+    /// Creates an empty block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a document. No conditional symbols are defined, so `[$WIN32]` evaluates to `false`
+    /// and `[!$WIN32]` evaluates to `true`.
+    ///
+    /// # Errors
+    /// - The document is malformed.
+    /// - The document contains a `#base` or `#include` directive. Use [`KeyValues::load`] to
+    ///   resolve these relative to a file.
+    pub fn parse(input: &str) -> Result<Self, KeyValuesError> {
+        Self::parse_with_conditions(input, &[])
+    }
+
+    /// Parses a document with the given conditional symbols defined e.g. `["$WIN32"]`.
+    pub fn parse_with_conditions(
+        input: &str,
+        conditions: &[&str],
+    ) -> Result<Self, KeyValuesError> {
+        let document = Parser::new(input, conditions).parse_document()?;
+
+        if let Some(directive) = document.directives.into_iter().next() {
+            return Err(KeyValuesError::UnresolvedDirective {
+                path: directive.path,
+            });
+        }
+
+        Ok(document.root)
+    }
+
+    /// Loads a document from a file, resolving `#base` and `#include` directives relative to the
+    /// file's directory. UTF-16 files with a byte order mark (as the localization files are) are
+    /// decoded.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KeyValuesError> {
+        Self::load_with_conditions(path, &[])
+    }
+
+    /// Loads a document from a file with the given conditional symbols defined.
+    pub fn load_with_conditions<P: AsRef<Path>>(
+        path: P,
+        conditions: &[&str],
+    ) -> Result<Self, KeyValuesError> {
+        let mut loading = HashSet::new();
+
+        load_file(path.as_ref(), conditions, &mut loading)
+    }
+
+    /// Gets the first value for `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Gets the first string value for `key`.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    /// Gets the first object value for `key`.
+    pub fn get_object(&self, key: &str) -> Option<&KeyValues> {
+        self.get(key).and_then(Value::as_object)
+    }
+
+    /// Gets all values for `key`.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Appends a key-value pair.
+    pub fn push<K: Into<String>>(&mut self, key: K, value: Value) {
+        self.entries.push((key.into(), value));
+    }
+
+    /// Sets the value for `key`, replacing the first existing value.
+    pub fn insert<K: Into<String>>(&mut self, key: K, value: Value) {
+        let key = key.into();
+
+        if let Some((_, existing)) = self.entries
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(&key)) {
+            *existing = value;
+        } else {
+            self.entries.push((key, value));
+        }
+    }
+
+    /// Removes all values for `key`.
+    pub fn remove(&mut self, key: &str) {
+        self.entries.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+    }
+
+    /// Iterates over the key-value pairs in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// The number of key-value pairs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the block has no key-value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Merges `other` on top of this block. Objects present in both are merged recursively,
+    /// anything else in `other` replaces the value in this block.
+    pub fn merge(&mut self, other: &KeyValues) {
+        for (key, value) in &other.entries {
+            let existing = self.entries
+                .iter_mut()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key));
+
+            match (existing, value) {
+                (Some((_, Value::Object(existing))), Value::Object(value)) => {
+                    existing.merge(value);
+                },
+                (Some((_, existing)), value) => *existing = value.clone(),
+                (None, value) => self.entries.push((key.clone(), value.clone())),
+            }
+        }
+    }
+
+    /// Resolves the `"prefab"` key of this block against `prefabs`. Prefabs are applied in the
+    /// order they are listed, each resolved recursively, and this block's own values are applied
+    /// last. The `"prefab"` key is not included in the output.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::keyvalues::KeyValues;
+    ///
+    /// let kv = KeyValues::parse(r#"
+    ///     "prefabs"
+    ///     {
+    ///         "valve" { "item_quality" "unique" "capabilities" { "paintable" "1" } }
+    ///         "weapon_rocketlauncher" { "prefab" "valve" "item_class" "tf_weapon_rocketlauncher" }
+    ///     }
+    ///     "item" { "prefab" "weapon_rocketlauncher" "item_quality" "normal" }
+    /// "#).unwrap();
+    /// let prefabs = kv.get_object("prefabs").unwrap();
+    /// let item = kv.get_object("item").unwrap().resolve_prefabs(prefabs).unwrap();
+    ///
+    /// assert_eq!(item.get_str("item_class"), Some("tf_weapon_rocketlauncher"));
+    /// assert_eq!(item.get_str("item_quality"), Some("normal"));
+    /// assert!(item.get("prefab").is_none());
+    /// ```
+    ///
+    /// # Errors
+    /// - A listed prefab does not exist.
+    /// - The prefabs reference each other in a cycle.
+    pub fn resolve_prefabs(&self, prefabs: &KeyValues) -> Result<KeyValues, KeyValuesError> {
+        resolve_prefabs(self, prefabs, 0)
+    }
+}
+
+fn resolve_prefabs(
+    kv: &KeyValues,
+    prefabs: &KeyValues,
+    depth: usize,
+) -> Result<KeyValues, KeyValuesError> {
+    let mut resolved = KeyValues::new();
+
+    if let Some(names) = kv.get_str("prefab") {
+        for name in names.split_whitespace() {
+            if depth >= MAX_PREFAB_DEPTH {
+                return Err(KeyValuesError::PrefabCycle {
+                    name: name.to_string(),
+                });
+            }
+
+            let prefab = prefabs.get_object(name)
+                .ok_or_else(|| KeyValuesError::UnknownPrefab {
+                    name: name.to_string(),
+                })?;
+
+            resolved.merge(&resolve_prefabs(prefab, prefabs, depth + 1)?);
+        }
+    }
+
+    resolved.merge(kv);
+    resolved.remove("prefab");
+    Ok(resolved)
+}
+
+/// A `#base` or `#include` directive.
+struct Directive {
+    path: String,
+    is_base: bool,
+}
+
+/// A parsed document prior to resolving its directives.
+struct Document {
+    root: KeyValues,
+    directives: Vec<Directive>,
+}
+
+/// Loads a file and the files it references. `loading` holds the files currently being loaded,
+/// from the first file down to this one, so a file may be referenced more than once as long as it
+/// doesn't reference itself.
+fn load_file(
+    path: &Path,
+    conditions: &[&str],
+    loading: &mut HashSet<PathBuf>,
+) -> Result<KeyValues, KeyValuesError> {
+    let io_error = |error| KeyValuesError::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+    let canonical = path.canonicalize().map_err(io_error)?;
+
+    if !loading.insert(canonical.clone()) {
+        return Err(KeyValuesError::DirectiveCycle {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let bytes = fs::read(path).map_err(io_error)?;
+    let input = decode(&bytes).ok_or_else(|| KeyValuesError::InvalidEncoding {
+        path: path.to_path_buf(),
+    })?;
+    let Document {
+        mut root,
+        directives,
+    } = Parser::new(&input, conditions).parse_document()?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    for directive in directives {
+        let included = load_file(&dir.join(&directive.path), conditions, loading)?;
+
+        if directive.is_base {
+            // Values in the including file take precedence over the base file.
+            let mut base = included;
+
+            base.merge(&root);
+            root = base;
+        } else {
+            root.entries.extend(included.entries);
+        }
+    }
+
+    loading.remove(&canonical);
+
+    Ok(root)
+}
+
+/// Decodes a file's bytes, detecting UTF-16 by its byte order mark.
+fn decode(bytes: &[u8]) -> Option<String> {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+        _ => String::from_utf8(bytes.to_vec()).ok(),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+
+    String::from_utf16(&units).ok()
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    String(String),
+    Open,
+    Close,
+    Condition(String),
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    conditions: &'a [&'a str],
+    line: usize,
+    peeked: Option<Token>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, conditions: &'a [&'a str]) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            conditions,
+            line: 1,
+            peeked: None,
+        }
+    }
+
+    fn parse_document(mut self) -> Result<Document, KeyValuesError> {
+        let mut directives = Vec::new();
+        let mut root = KeyValues::new();
+
+        while let Some(token) = self.next_token()? {
+            match token {
+                Token::String(key) if key.eq_ignore_ascii_case("#base") ||
+                    key.eq_ignore_ascii_case("#include") => {
+                    let path = match self.next_token()? {
+                        Some(Token::String(path)) => path,
+                        _ => return Err(self.syntax_error("expected a path after directive")),
+                    };
+
+                    directives.push(Directive {
+                        path,
+                        is_base: key.eq_ignore_ascii_case("#base"),
+                    });
+                },
+                Token::String(key) => self.parse_pair(&mut root, key)?,
+                _ => return Err(self.syntax_error("expected a key")),
+            }
+        }
+
+        Ok(Document {
+            root,
+            directives,
+        })
+    }
+
+    fn parse_block(&mut self) -> Result<KeyValues, KeyValuesError> {
+        let mut block = KeyValues::new();
+
+        loop {
+            match self.next_token()? {
+                Some(Token::Close) => return Ok(block),
+                Some(Token::String(key)) => self.parse_pair(&mut block, key)?,
+                Some(_) => return Err(self.syntax_error("expected a key")),
+                None => return Err(KeyValuesError::UnexpectedEof),
+            }
+        }
+    }
+
+    /// Parses the value following `key` and adds it to `block` if its condition holds.
+    fn parse_pair(&mut self, block: &mut KeyValues, key: String) -> Result<(), KeyValuesError> {
+        let mut enabled = true;
+        let mut token = self.next_token()?;
+
+        // Conditions may come between the key and a block.
+        if let Some(Token::Condition(condition)) = token {
+            enabled = self.evaluate(&condition);
+            token = self.next_token()?;
+        }
+
+        let value = match token {
+            Some(Token::String(value)) => Value::String(value),
+            Some(Token::Open) => Value::Object(self.parse_block()?),
+            Some(_) => return Err(self.syntax_error("expected a value")),
+            None => return Err(KeyValuesError::UnexpectedEof),
+        };
+
+        // Or after the value.
+        if let Some(Token::Condition(_)) = self.peek_token()? {
+            if let Some(Token::Condition(condition)) = self.next_token()? {
+                enabled = enabled && self.evaluate(&condition);
+            }
+        }
+
+        if enabled {
+            block.push(key, value);
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates a condition such as `$WIN32`, `!$X360` or `$WIN32||$OSX`.
+    fn evaluate(&self, condition: &str) -> bool {
+        condition
+            .split("||")
+            .any(|any| any
+                .split("&&")
+                .all(|term| {
+                    let term = term.trim();
+
+                    if let Some(term) = term.strip_prefix('!') {
+                        !self.is_defined(term.trim())
+                    } else {
+                        self.is_defined(term)
+                    }
+                }))
+    }
+
+    fn is_defined(&self, symbol: &str) -> bool {
+        self.conditions
+            .iter()
+            .any(|defined| defined.eq_ignore_ascii_case(symbol))
+    }
+
+    fn syntax_error(&self, message: &'static str) -> KeyValuesError {
+        KeyValuesError::Syntax {
+            line: self.line,
+            message,
+        }
+    }
+
+    fn peek_token(&mut self) -> Result<Option<&Token>, KeyValuesError> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_token()?;
+        }
+
+        Ok(self.peeked.as_ref())
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, KeyValuesError> {
+        if let Some(token) = self.peeked.take() {
+            return Ok(Some(token));
+        }
+
+        self.read_token()
+    }
+
+    fn read_token(&mut self) -> Result<Option<Token>, KeyValuesError> {
+        self.skip_whitespace_and_comments();
+
+        let c = match self.chars.next() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+
+        match c {
+            '{' => Ok(Some(Token::Open)),
+            '}' => Ok(Some(Token::Close)),
+            '"' => self.read_quoted().map(|s| Some(Token::String(s))),
+            '[' => {
+                let mut condition = String::new();
+
+                loop {
+                    match self.chars.next() {
+                        Some(']') => break,
+                        Some('\n') | None => return Err(self.syntax_error("unclosed condition")),
+                        Some(c) => condition.push(c),
+                    }
+                }
+
+                Ok(Some(Token::Condition(condition)))
+            },
+            c => {
+                let mut s = String::from(c);
+
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_whitespace() || matches!(c, '{' | '}' | '"') {
+                        break;
+                    }
+
+                    s.push(c);
+                    self.chars.next();
+                }
+
+                Ok(Some(Token::String(s)))
+            },
+        }
+    }
+
+    fn read_quoted(&mut self) -> Result<String, KeyValuesError> {
+        let mut s = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some('\\') => s.push('\\'),
+                    Some('"') => s.push('"'),
+                    Some(c) => {
+                        s.push('\\');
+                        s.push(c);
+                    },
+                    None => return Err(KeyValuesError::UnexpectedEof),
+                },
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+
+                    s.push(c);
+                },
+                None => return Err(KeyValuesError::UnexpectedEof),
+            }
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == '\n' {
+                self.line += 1;
+                self.chars.next();
+            } else if c.is_whitespace() {
+                self.chars.next();
+            } else if c == '/' {
+                let mut lookahead = self.chars.clone();
+
+                lookahead.next();
+
+                if lookahead.peek() != Some(&'/') {
+                    return;
+                }
+
+                while let Some(c) = self.chars.peek() {
+                    if *c == '\n' {
+                        break;
+                    }
+
+                    self.chars.next();
+                }
+            } else {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_blocks() {
+        let kv = KeyValues::parse(r#"
+            // comment
+            "root"
+            {
+                "a" "1"
+                unquoted value
+                "nested" { "b" "2" }
+            }
+        "#).unwrap();
+        let root = kv.get_object("root").unwrap();
+
+        assert_eq!(root.get_str("a"), Some("1"));
+        assert_eq!(root.get_str("unquoted"), Some("value"));
+        assert_eq!(root.get_object("nested").unwrap().get_str("b"), Some("2"));
+    }
+
+    #[test]
+    fn keys_are_case_insensitive() {
+        let kv = KeyValues::parse(r#""Tokens" { "TF_Weapon" "Rocket Launcher" }"#).unwrap();
+
+        assert_eq!(kv.get_object("tokens").unwrap().get_str("tf_weapon"), Some("Rocket Launcher"));
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let kv = KeyValues::parse(r#""a" "line\n\"quoted\"""#).unwrap();
+
+        assert_eq!(kv.get_str("a"), Some("line\n\"quoted\""));
+    }
+
+    #[test]
+    fn evaluates_conditions() {
+        let input = r#"
+            "windows" "1" [$WIN32]
+            "not_console" "1" [!$X360]
+            "either" [$OSX||$WIN32] { "a" "b" }
+        "#;
+        let kv = KeyValues::parse(input).unwrap();
+
+        assert!(kv.get("windows").is_none());
+        assert!(kv.get("not_console").is_some());
+        assert!(kv.get("either").is_none());
+
+        let kv = KeyValues::parse_with_conditions(input, &["$WIN32"]).unwrap();
+
+        assert!(kv.get("windows").is_some());
+        assert!(kv.get("either").is_some());
+    }
+
+    #[test]
+    fn base_without_path_is_err() {
+        assert!(matches!(
+            KeyValues::parse("#base \"other.txt\" \"a\" \"b\""),
+            Err(KeyValuesError::UnresolvedDirective { .. }),
+        ));
+    }
+
+    #[test]
+    fn loads_files_included_twice() {
+        let kv = KeyValues::load("tests/fixtures/keyvalues/diamond.txt").unwrap();
+
+        for key in ["diamond", "left", "right", "shared"] {
+            assert_eq!(kv.get_object(key).unwrap().get_str("name"), Some(key));
+        }
+    }
+
+    #[test]
+    fn directive_cycle_is_err() {
+        assert!(matches!(
+            KeyValues::load("tests/fixtures/keyvalues/cycle.txt"),
+            Err(KeyValuesError::DirectiveCycle { .. }),
+        ));
+    }
+
+    #[test]
+    fn unclosed_block_is_err() {
+        assert!(matches!(
+            KeyValues::parse("\"a\" { \"b\" \"c\""),
+            Err(KeyValuesError::UnexpectedEof),
+        ));
+    }
+
+    #[test]
+    fn prefab_cycle_is_err() {
+        let kv = KeyValues::parse(r#"
+            "a" { "prefab" "b" }
+            "b" { "prefab" "a" }
+        "#).unwrap();
+
+        assert!(matches!(
+            kv.get_object("a").unwrap().resolve_prefabs(&kv),
+            Err(KeyValuesError::PrefabCycle { .. }),
+        ));
+    }
+
+    #[test]
+    fn decodes_utf16() {
+        let mut bytes = vec![0xFF, 0xFE];
+
+        bytes.extend("\"a\" \"Ü\"".encode_utf16().flat_map(u16::to_le_bytes));
+
+        assert_eq!(decode(&bytes).unwrap(), "\"a\" \"Ü\"");
+    }
+}
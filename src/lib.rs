@@ -20,10 +20,41 @@
 
 #![warn(missing_docs)]
 
+pub mod backpack_tf;
+pub mod change;
+pub mod community;
+pub mod currencies;
+pub mod describe;
 pub mod error;
+pub mod keyvalues;
+pub mod locale;
+pub mod price_table;
+pub mod prices_tf;
+pub mod render;
+pub mod schema;
+pub mod search;
+pub mod shorthand;
+pub mod url;
+pub mod valuation;
+pub mod web_api;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+#[cfg(feature = "static-schema")]
+pub mod static_schema;
+#[cfg(feature = "gc")]
+pub mod gc;
+#[cfg(feature = "tf2autobot")]
+pub mod tf2autobot;
 
+mod attributes;
 mod helpers;
+mod name;
+mod price_index;
+mod resolve;
 mod sku;
 
+pub use attributes::RecipeOutput;
+pub use resolve::{InputKind, Resolution, resolve};
+pub use schema::{ItemSchema, Schema, SchemaItem};
 pub use sku::{SKU, SKUString};
 pub use tf2_enum;
@@ -0,0 +1,364 @@
+//! Localization tokens from the game's `tf_<language>.txt` files, used for localized item names.
+//!
+//! Schemas loaded from `items_game.txt` name items, effects and paint kits by token (e.g.
+//! "#TF_Weapon_RocketLauncher"). Those tokens are resolved from the loaded files. The other words
+//! in a name are looked up by these tokens, falling back to English when missing:
+//!
+//! | Word | Token |
+//! |---|---|
+//! | Qualities | The quality's name in `items_game.txt` e.g. "rarity4" for Unusual |
+//! | Killstreak tiers | "ItemNameKillStreakv0" to "ItemNameKillStreakv2" |
+//! | Sheens | "Attrib_KillStreakIdleEffect1" to "Attrib_KillStreakIdleEffect7" |
+//! | Killstreakers | "Attrib_KillStreakEffect2002" to "Attrib_KillStreakEffect2008" |
+//! | Wears | "TFUI_InvTooltip_FactoryNew" to "TFUI_InvTooltip_BattleScared" |
+//! | Festivized | "ItemNameFestive" |
+//! | Australium | "ItemNameAustralium" |
+//! | Non-Craftable | "ItemNameUncraftable" |
+//! | The | "TF_Unique_Prepend_Proper_Quality" |
+//!
+//! Missing tokens can be added with [`Localization::insert`].
+
+use crate::SKU;
+use crate::error::{KeyValuesError, NameParseError};
+use crate::keyvalues::KeyValues;
+use crate::name::Vocabulary;
+use crate::schema::{ItemSchema, Schema};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use tf2_enum::{KillstreakTier, Killstreaker, Quality, Sheen, Wear};
+
+/// The suffix of protobuf definition names in `tf_proto_obj_defs_<language>.txt`.
+const PROTO_DEF_NAME_FIELD: &str = " { field_number: 2 }";
+
+/// Localization tokens for a language.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{SKU, Schema, SchemaItem};
+/// use tf2_sku::keyvalues::KeyValues;
+/// use tf2_sku::locale::Localization;
+/// use tf2_enum::Quality;
+///
+/// let locale = Localization::from_keyvalues(&KeyValues::parse(r#"
+///     "lang"
+///     {
+///         "Language" "german"
+///         "Tokens"
+///         {
+///             "TF_Weapon_RocketLauncher" "Raketenwerfer"
+///             "strange" "Seltsam"
+///         }
+///     }
+/// "#).unwrap());
+/// let mut schema = Schema::default();
+///
+/// schema.items.insert(205, SchemaItem {
+///     defindex: 205,
+///     name: "Upgradeable TF_WEAPON_ROCKETLAUNCHER".into(),
+///     item_name: "#TF_Weapon_RocketLauncher".into(),
+///     proper_name: false,
+///     item_class: "tf_weapon_rocketlauncher".into(),
+///     item_quality: Quality::Unique,
+///     craft_class: None,
+///     item_slot: None,
+///     tool_type: None,
+///     crate_series: None,
+///     paint_kit: None,
+///     capabilities: Default::default(),
+/// });
+///
+/// let sku = "205;11".parse::<SKU>().unwrap();
+///
+/// assert_eq!(sku.localized_name(&schema, &locale), "Seltsam Raketenwerfer");
+/// assert_eq!(SKU::from_localized_name("Seltsam Raketenwerfer", &schema, &locale), Ok(sku));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Localization {
+    language: Option<String>,
+    /// Values by lowercase token.
+    tokens: HashMap<String, String>,
+}
+
+impl Localization {
+    /// Reads a localization file, e.g. `tf_english.txt` or `tf_proto_obj_defs_english.txt`.
+    /// Files with a byte order mark are decoded as UTF-16.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KeyValuesError> {
+        Ok(Self::from_keyvalues(&KeyValues::load(path)?))
+    }
+
+    /// Reads localization tokens from a parsed file. The tokens are read from the "Tokens" block,
+    /// within a "lang" block if there is one.
+    ///
+    /// `tf_proto_obj_defs_<language>.txt` keys names as e.g. "9_310_field { field_number: 2 }",
+    /// which are read as the token "9_310_field".
+    pub fn from_keyvalues(kv: &KeyValues) -> Self {
+        let lang = kv.get_object("lang").unwrap_or(kv);
+        let tokens = lang.get_object("Tokens")
+            .map(|tokens| tokens
+                .iter()
+                .filter_map(|(token, value)| {
+                    let token = token.strip_suffix(PROTO_DEF_NAME_FIELD).unwrap_or(token);
+
+                    Some((token.to_lowercase(), value.as_str()?.to_string()))
+                })
+                .collect())
+            .unwrap_or_default();
+
+        Self {
+            language: lang.get_str("Language").map(str::to_string),
+            tokens,
+        }
+    }
+
+    /// The language of the tokens e.g. "english", if known.
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// Adds the tokens from another file of the same language, replacing existing values.
+    pub fn extend(&mut self, other: Localization) {
+        if self.language.is_none() {
+            self.language = other.language;
+        }
+
+        self.tokens.extend(other.tokens);
+    }
+
+    /// Adds a token, replacing an existing value.
+    pub fn insert<T: Into<String>, V: Into<String>>(&mut self, token: T, value: V) {
+        self.tokens.insert(token.into().to_lowercase(), value.into());
+    }
+
+    /// Gets the value of a token. A leading "#" is ignored and tokens are case insensitive.
+    pub fn get(&self, token: &str) -> Option<&str> {
+        let token = token.strip_prefix('#').unwrap_or(token);
+
+        self.tokens.get(&token.to_lowercase()).map(String::as_str)
+    }
+
+    /// Resolves a name that may be a token (beginning with "#"). Names that aren't tokens, and
+    /// tokens that aren't found, are returned unchanged.
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        if name.starts_with('#') {
+            self.get(name).unwrap_or(name)
+        } else {
+            name
+        }
+    }
+
+    /// Creates a copy of a schema with its item, effect and paint kit names resolved.
+    pub fn localize(&self, schema: &Schema) -> Schema {
+        let mut schema = schema.clone();
+
+        for item in schema.items.values_mut() {
+            if let Some(name) = self.get_token(&item.item_name) {
+                item.item_name = name.to_string().into();
+            }
+        }
+
+        for name in schema.particles.values_mut().chain(schema.paint_kits.values_mut()) {
+            if let Some(resolved) = self.get_token(name) {
+                *name = resolved.to_string();
+            }
+        }
+
+        schema
+    }
+
+    fn get_token(&self, name: &str) -> Option<&str> {
+        if name.starts_with('#') {
+            self.get(name)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a word used in names.
+    fn find_word(&self, word: Word) -> Option<&str> {
+        let value = self.get(&word.token())?.trim();
+
+        // Wears are in parentheses e.g. "(Field-Tested)".
+        Some(value
+            .strip_prefix('(')
+            .and_then(|value| value.strip_suffix(')'))
+            .map(str::trim)
+            .unwrap_or(value))
+    }
+}
+
+/// A word used in names other than the names from the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Word {
+    NonCraftable,
+    Quality(Quality),
+    KillstreakTier(KillstreakTier),
+    Sheen(Sheen),
+    Killstreaker(Killstreaker),
+    Wear(Wear),
+    Festivized,
+    Australium,
+    The,
+}
+
+impl Word {
+    fn token(self) -> Cow<'static, str> {
+        match self {
+            Word::NonCraftable => "ItemNameUncraftable".into(),
+            Word::Quality(quality) => quality_token(quality).into(),
+            Word::KillstreakTier(killstreak_tier) => {
+                format!("ItemNameKillStreakv{}", u32::from(killstreak_tier) - 1).into()
+            },
+            Word::Sheen(sheen) => format!("Attrib_KillStreakIdleEffect{}", u32::from(sheen)).into(),
+            Word::Killstreaker(killstreaker) => {
+                format!("Attrib_KillStreakEffect{}", u32::from(killstreaker)).into()
+            },
+            Word::Wear(wear) => match wear {
+                Wear::FactoryNew => "TFUI_InvTooltip_FactoryNew",
+                Wear::MinimalWear => "TFUI_InvTooltip_MinimalWear",
+                Wear::FieldTested => "TFUI_InvTooltip_FieldTested",
+                Wear::WellWorn => "TFUI_InvTooltip_WellWorn",
+                // Misspelled in the game files.
+                Wear::BattleScarred => "TFUI_InvTooltip_BattleScared",
+            }.into(),
+            Word::Festivized => "ItemNameFestive".into(),
+            Word::Australium => "ItemNameAustralium".into(),
+            Word::The => "TF_Unique_Prepend_Proper_Quality".into(),
+        }
+    }
+
+    /// Gets the word in English.
+    pub(crate) fn english(self) -> Cow<'static, str> {
+        match self {
+            Word::NonCraftable => "Non-Craftable".into(),
+            Word::Quality(quality) => quality.to_string().into(),
+            Word::KillstreakTier(killstreak_tier) => killstreak_tier.to_string().into(),
+            Word::Sheen(sheen) => sheen.to_string().into(),
+            Word::Killstreaker(killstreaker) => killstreaker.to_string().into(),
+            Word::Wear(wear) => wear.to_string().into(),
+            Word::Festivized => "Festivized".into(),
+            Word::Australium => "Australium".into(),
+            Word::The => "The".into(),
+        }
+    }
+}
+
+impl Vocabulary for Localization {
+    fn word(&self, word: Word) -> Cow<'_, str> {
+        self.find_word(word)
+            .map(Cow::Borrowed)
+            .unwrap_or_else(|| word.english())
+    }
+
+    fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        Localization::resolve(self, name)
+    }
+}
+
+/// Gets the token for a quality, which is its name in `items_game.txt`.
+fn quality_token(quality: Quality) -> &'static str {
+    match quality {
+        Quality::Normal => "normal",
+        Quality::Genuine => "rarity1",
+        Quality::Rarity2 => "rarity2",
+        Quality::Vintage => "vintage",
+        Quality::Rarity3 => "rarity3",
+        Quality::Unusual => "rarity4",
+        Quality::Unique => "unique",
+        Quality::Community => "community",
+        Quality::Valve => "developer",
+        Quality::SelfMade => "selfmade",
+        Quality::Customized => "customized",
+        Quality::Strange => "strange",
+        Quality::Completed => "completed",
+        Quality::Haunted => "haunted",
+        Quality::Collectors => "collectors",
+        Quality::DecoratedWeapon => "paintkitweapon",
+    }
+}
+
+impl SKU {
+    /// Gets the full name of the item in a language. This is the same as [`SKU::name`] with the
+    /// names resolved from `locale`, keeping the English word order.
+    pub fn localized_name<S: ItemSchema + ?Sized>(
+        &self,
+        schema: &S,
+        locale: &Localization,
+    ) -> String {
+        self.name_in(schema, locale)
+    }
+
+    /// Parses a full item name in a language, as produced by [`SKU::localized_name`].
+    ///
+    /// # Errors
+    /// The same as [`SKU::from_name`].
+    pub fn from_localized_name<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+        locale: &Localization,
+    ) -> Result<SKU, NameParseError> {
+        SKU::from_name_in(name, schema, &[locale])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale() -> Localization {
+        Localization::load("tests/fixtures/tf_german.txt").unwrap()
+    }
+
+    fn schema() -> Schema {
+        Schema::load_items_game("tests/fixtures/items_game.txt").unwrap()
+    }
+
+    #[test]
+    fn loads_utf16_tokens() {
+        let locale = locale();
+
+        assert_eq!(locale.language(), Some("german"));
+        assert_eq!(locale.get("#TF_Weapon_RocketLauncher"), Some("Raketenwerfer"));
+        assert_eq!(locale.get("tf_weapon_rocketlauncher"), Some("Raketenwerfer"));
+        assert_eq!(locale.resolve("#Unknown_Token"), "#Unknown_Token");
+    }
+
+    #[test]
+    fn localizes_names() {
+        let locale = locale();
+        let schema = schema();
+        let name = |sku: &str| sku.parse::<SKU>().unwrap().localized_name(&schema, &locale);
+
+        assert_eq!(name("205;11;kt-3;w3;pk310"), "Seltsam Professioneller Killstreak Warnstufe Rot Raketenwerfer (Einsatzerprobt)");
+        assert_eq!(name("378;5;u13"), "Brennende Flammen Teamkapitän");
+        assert_eq!(name("378;6"), "Der Teamkapitän");
+        // Missing tokens fall back to English.
+        assert_eq!(name("205;6;festive"), "Festivized Raketenwerfer");
+    }
+
+    #[test]
+    fn parses_localized_names() {
+        let locale = locale();
+        let schema = schema();
+
+        for s in ["205;11;kt-3;w3;pk310", "378;5;u13", "378;6", "205;6;uncraftable"] {
+            let sku = s.parse::<SKU>().unwrap();
+            let name = sku.localized_name(&schema, &locale);
+
+            assert_eq!(SKU::from_localized_name(&name, &schema, &locale), Ok(sku), "{name}");
+        }
+    }
+
+    #[test]
+    fn localizes_schemas() {
+        let schema = locale().localize(&schema());
+
+        assert_eq!(schema.item(378).unwrap().item_name, "Teamkapitän");
+        assert_eq!(schema.particle_name(13), Some("Brennende Flammen"));
+        assert_eq!(schema.paint_kit_name(310), Some("Warnstufe Rot"));
+        assert_eq!(schema.item(6526).unwrap().item_name, "#TF_KillStreakifierToolB");
+    }
+}
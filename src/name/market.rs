@@ -0,0 +1,134 @@
+//! Steam Community Market names.
+
+use crate::SKU;
+use crate::error::NameParseError;
+use super::{English, Vocabulary};
+use crate::schema::ItemSchema;
+use tf2_enum::{SpellSet, StrangePartSet};
+
+impl SKU {
+    /// Gets the `market_hash_name` of the item on the Steam Community Market e.g. "Strange
+    /// Professional Killstreak Rocket Launcher (Factory New)".
+    ///
+    /// Market names differ from [`SKU::name`]: effects, sheens, killstreakers and craft numbers
+    /// are left out, Unusual items always say "Unusual", items are never "Non-Craftable", and
+    /// crates are numbered by series e.g. "Mann Co. Supply Crate Series #1".
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    ///
+    /// let sku = "205;5;u13;kt-3;ks-1;uncraftable;n100".parse::<SKU>().unwrap();
+    ///
+    /// assert_eq!(
+    ///     sku.market_hash_name(&Schema::default()),
+    ///     "Unusual Professional Killstreak Item #205",
+    /// );
+    /// ```
+    pub fn market_hash_name<S: ItemSchema + ?Sized>(&self, schema: &S) -> String {
+        let sku = SKU {
+            craftable: true,
+            particle: None,
+            sheen: None,
+            killstreaker: None,
+            craft_number: None,
+            crate_number: None,
+            paint: None,
+            spells: SpellSet::default(),
+            strange_parts: StrangePartSet::default(),
+            ..*self
+        };
+        let mut name = sku.name_in(schema, &English);
+
+        if let Some(crate_number) = self.crate_number {
+            name.push_str(&format!(" Series #{crate_number}"));
+        }
+
+        name
+    }
+
+    /// Parses a `market_hash_name` into the SKUs sharing it. Attributes left out of market
+    /// names, such as effects and craftability, are left unset, so every SKU which
+    /// [`SKU::market_hash_name`] gives the same name for matches one of the returned SKUs once
+    /// those attributes are cleared.
+    ///
+    /// # Errors
+    /// Returns [`NameParseError::Empty`] or [`NameParseError::UnknownItem`] if the name can't be
+    /// parsed. Names matching more than one item aren't an error.
+    pub fn from_market_hash_name<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+    ) -> Result<Vec<SKU>, NameParseError> {
+        SKU::from_market_hash_name_in(name, schema, &[&English])
+    }
+
+    /// Parses a `market_hash_name` using the words from any of `vocabularies`. Names from the
+    /// schema are resolved with the first.
+    pub(crate) fn from_market_hash_name_in<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+        vocabularies: &[&dyn Vocabulary],
+    ) -> Result<Vec<SKU>, NameParseError> {
+        let name = name.trim();
+        let name = match name.rsplit_once(" Series #") {
+            Some((rest, number)) if number.bytes().all(|b| b.is_ascii_digit()) => {
+                format!("{rest} #{number}")
+            },
+            _ => name.to_string(),
+        };
+
+        SKU::candidates_in(&name, schema, vocabularies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn market_hash_name(sku: &str) -> String {
+        sku.parse::<SKU>().unwrap().market_hash_name(&schema())
+    }
+
+    fn parse(name: &str) -> Vec<String> {
+        SKU::from_market_hash_name(name, &schema())
+            .unwrap()
+            .iter()
+            .map(|sku| sku.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn formats_market_hash_names() {
+        assert_eq!(market_hash_name("205;11;kt-3;ks-1;ke-2008;sp-28"), "Strange Professional Killstreak Rocket Launcher");
+        assert_eq!(market_hash_name("205;15;w1;pk102;uncraftable"), "Warhawk Rocket Launcher (Factory New)");
+        assert_eq!(market_hash_name("378;5;u13"), "Unusual Team Captain");
+        assert_eq!(market_hash_name("378;5;u13;strange"), "Strange Unusual Team Captain");
+        assert_eq!(market_hash_name("378;6;n100"), "The Team Captain");
+        assert_eq!(market_hash_name("5041;6;c2"), "Mann Co. Supply Crate Series #2");
+    }
+
+    #[test]
+    fn parses_market_hash_names() {
+        assert_eq!(parse("Strange Professional Killstreak Rocket Launcher"), ["205;11;kt-3"]);
+        assert_eq!(parse("Unusual Team Captain"), ["378;5"]);
+        assert_eq!(parse("Warhawk Rocket Launcher (Factory New)"), ["205;15;w1;pk102"]);
+        assert_eq!(parse("Mann Co. Supply Crate Series #2"), ["5041;6;c2"]);
+        assert_eq!(parse("Shotgun"), ["199;6"]);
+        assert!(SKU::from_market_hash_name("Unknown Hat", &schema()).is_err());
+    }
+
+    #[test]
+    fn round_trips_market_hash_names() {
+        for sku in ["205;11;kt-3;festive", "378;5;u702;strange", "16102;15;w3;pk102", "5022;6;c1"] {
+            let sku = sku.parse::<SKU>().unwrap();
+            let name = sku.market_hash_name(&schema());
+            let market_sku = SKU {
+                particle: None,
+                ..sku
+            };
+
+            assert!(SKU::from_market_hash_name(&name, &schema()).unwrap().contains(&market_sku), "{name}");
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Item names, as shown in-game and on backpack.tf.
+
+mod market;
+mod parse;
+
+pub(crate) use parse::narrow;
+
+use crate::SKU;
+use crate::locale::Word;
+use crate::schema::ItemSchema;
+use std::borrow::Cow;
+use tf2_enum::Quality;
+
+impl SKU {
+    /// Gets the full name of the item e.g. "Strange Professional Killstreak Rocket Launcher
+    /// (Field-Tested)". Names follow backpack.tf, with the sheen and killstreaker names after the
+    /// killstreak tier when present.
+    ///
+    /// Anything missing from the schema is named by its ID instead, e.g. "Item #205" or
+    /// "Effect #13".
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    ///
+    /// let schema = Schema::default();
+    /// let sku = "205;11;kt-3;w2".parse::<SKU>().unwrap();
+    ///
+    /// assert_eq!(sku.name(&schema), "Strange Professional Killstreak Item #205 (Minimal Wear)");
+    /// ```
+    pub fn name<S: ItemSchema + ?Sized>(&self, schema: &S) -> String {
+        self.name_in(schema, &English)
+    }
+
+    /// Gets the full name of the item using the words from `vocabulary`.
+    pub(crate) fn name_in<S: ItemSchema + ?Sized>(
+        &self,
+        schema: &S,
+        vocabulary: &dyn Vocabulary,
+    ) -> String {
+        let mut prefixes: Vec<Cow<str>> = Vec::new();
+        // An elevated quality e.g. "Strange Unusual".
+        let elevated = self.strange && self.quality != Quality::Strange;
+
+        if !self.craftable {
+            prefixes.push(vocabulary.word(Word::NonCraftable));
+        }
+
+        if elevated {
+            prefixes.push(vocabulary.word(Word::Quality(Quality::Strange)));
+        }
+
+        if has_quality_prefix(self, elevated) {
+            prefixes.push(vocabulary.word(Word::Quality(self.quality)));
+        }
+
+        if let Some(particle) = self.particle {
+            prefixes.push(vocabulary.particle_name(particle)
+                .map(Cow::Borrowed)
+                .unwrap_or_else(|| localize(vocabulary, particle_name(schema, particle))));
+        }
+
+        if let Some(killstreak_tier) = self.killstreak_tier {
+            prefixes.push(vocabulary.word(Word::KillstreakTier(killstreak_tier)));
+        }
+
+        if let Some(sheen) = self.sheen {
+            prefixes.push(vocabulary.word(Word::Sheen(sheen)));
+        }
+
+        if let Some(killstreaker) = self.killstreaker {
+            prefixes.push(vocabulary.word(Word::Killstreaker(killstreaker)));
+        }
+
+        if self.festivized {
+            prefixes.push(vocabulary.word(Word::Festivized));
+        }
+
+        // Chemistry sets name the quality of their output e.g. "Collector's".
+        if let Some(output_quality) = self.output_quality {
+            if output_quality != Quality::Unique {
+                prefixes.push(vocabulary.word(Word::Quality(output_quality)));
+            }
+        }
+
+        if let Some(target_defindex) = self.target_defindex {
+            prefixes.push(localize(vocabulary, item_name(schema, target_defindex)));
+        }
+
+        if let Some(output_defindex) = self.output_defindex {
+            prefixes.push(localize(vocabulary, item_name(schema, output_defindex)));
+        }
+
+        if let (Some(wear), true) = (self.wear, vocabulary.wear_first()) {
+            prefixes.push(vocabulary.word(Word::Wear(wear)));
+        }
+
+        if self.australium {
+            prefixes.push(vocabulary.word(Word::Australium));
+        }
+
+        if let Some(skin) = self.skin {
+            prefixes.push(localize(vocabulary, paint_kit_name(schema, skin)));
+        }
+
+        let mut name = prefixes.join(" ");
+
+        match schema.sku_item(self) {
+            Some(item) => {
+                if name.is_empty() {
+                    if item.proper_name {
+                        name.push_str(&vocabulary.word(Word::The));
+                        name.push(' ');
+                    }
+                } else {
+                    name.push(' ');
+                }
+
+                name.push_str(vocabulary.resolve(&item.item_name));
+            },
+            None => {
+                if !name.is_empty() {
+                    name.push(' ');
+                }
+
+                name.push_str(&format!("Item #{}", self.defindex));
+            },
+        }
+
+        if let (Some(wear), false) = (self.wear, vocabulary.wear_first()) {
+            name.push_str(&format!(" ({})", vocabulary.word(Word::Wear(wear))));
+        }
+
+        if let Some(crate_number) = self.crate_number {
+            name.push_str(&format!(" #{crate_number}"));
+        } else if let Some(craft_number) = self.craft_number {
+            name.push_str(&format!(" #{craft_number}"));
+        }
+
+        name
+    }
+}
+
+/// Checks whether the quality is named. Unique and Decorated Weapon are implied, as is Unusual
+/// when an effect is named unless the item is also strange.
+fn has_quality_prefix(sku: &SKU, elevated: bool) -> bool {
+    match sku.quality {
+        Quality::Unique |
+        Quality::DecoratedWeapon => false,
+        Quality::Unusual => sku.particle.is_none() || elevated,
+        _ => true,
+    }
+}
+
+/// The words names are built from and read with.
+pub(crate) trait Vocabulary {
+    /// Gets a word other than the names from the schema.
+    fn word(&self, word: Word) -> Cow<'_, str>;
+
+    /// Resolves a name from the schema, which may be a localization token.
+    fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        name
+    }
+
+    /// Gets a replacement for the name of a particle effect.
+    fn particle_name(&self, _particle: u32) -> Option<&str> {
+        None
+    }
+
+    /// Whether the wear goes before the item name instead of after it in parentheses.
+    fn wear_first(&self) -> bool {
+        false
+    }
+}
+
+/// Names as shown in-game in English.
+pub(crate) struct English;
+
+impl Vocabulary for English {
+    fn word(&self, word: Word) -> Cow<'_, str> {
+        word.english()
+    }
+}
+
+/// Resolves a name from the schema. Fallback names are left as they are.
+fn localize<'a>(vocabulary: &'a dyn Vocabulary, name: Cow<'a, str>) -> Cow<'a, str> {
+    match name {
+        Cow::Borrowed(name) => Cow::Borrowed(vocabulary.resolve(name)),
+        Cow::Owned(name) => Cow::Owned(name),
+    }
+}
+
+/// Gets the display name of an item, falling back to its defindex.
+pub(crate) fn item_name<S: ItemSchema + ?Sized>(schema: &S, defindex: u32) -> Cow<'_, str> {
+    schema.item(defindex)
+        .map(|item| Cow::Borrowed(item.item_name.as_ref()))
+        .unwrap_or_else(|| format!("Item #{defindex}").into())
+}
+
+/// Gets the name of a particle effect, falling back to its ID.
+pub(crate) fn particle_name<S: ItemSchema + ?Sized>(schema: &S, particle: u32) -> Cow<'_, str> {
+    schema.particle_name(particle)
+        .map(Cow::Borrowed)
+        .unwrap_or_else(|| format!("Effect #{particle}").into())
+}
+
+/// Gets the name of a paint kit, falling back to its ID.
+pub(crate) fn paint_kit_name<S: ItemSchema + ?Sized>(schema: &S, paint_kit: u32) -> Cow<'_, str> {
+    schema.paint_kit_name(paint_kit)
+        .map(Cow::Borrowed)
+        .unwrap_or_else(|| format!("Paint Kit #{paint_kit}").into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::Schema;
+
+    pub(crate) fn schema() -> Schema {
+        let json = std::fs::read_to_string("tests/fixtures/schema.json").unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn name(sku: &str) -> String {
+        sku.parse::<SKU>().unwrap().name(&schema())
+    }
+
+    #[test]
+    fn names_qualities() {
+        assert_eq!(name("205;6"), "Rocket Launcher");
+        assert_eq!(name("18;0"), "Normal Rocket Launcher");
+        assert_eq!(name("205;11;kt-3;ks-1;ke-2008;festive;australium;w3"), "Strange Professional Killstreak Team Shine Hypno-Beam Festivized Australium Rocket Launcher (Field-Tested)");
+        assert_eq!(name("205;6;uncraftable;strange"), "Non-Craftable Strange Rocket Launcher");
+        assert_eq!(name("378;5"), "Unusual Team Captain");
+    }
+
+    #[test]
+    fn names_effects() {
+        assert_eq!(name("378;5;u13"), "Burning Flames Team Captain");
+        assert_eq!(name("378;5;u13;strange"), "Strange Unusual Burning Flames Team Captain");
+        assert_eq!(name("378;5;u9999"), "Effect #9999 Team Captain");
+    }
+
+    #[test]
+    fn names_the_prefix() {
+        assert_eq!(name("378;6"), "The Team Captain");
+        assert_eq!(name("378;1"), "Genuine Team Captain");
+        assert_eq!(name("378;6;n42"), "The Team Captain #42");
+    }
+
+    #[test]
+    fn names_war_paints() {
+        assert_eq!(name("205;15;w1;pk102"), "Warhawk Rocket Launcher (Factory New)");
+        assert_eq!(name("205;11;w2;pk104"), "Strange Airwolf Rocket Launcher (Minimal Wear)");
+        assert_eq!(name("16102;15;w3;pk102"), "Warhawk War Paint (Field-Tested)");
+    }
+
+    #[test]
+    fn names_tools() {
+        assert_eq!(name("6527;6;kt-2;td-200"), "Specialized Killstreak Scattergun Kit");
+        assert_eq!(name("20002;6;kt-3;td-205;od-6527;oq-6"), "Professional Killstreak Rocket Launcher Kit Fabricator");
+        assert_eq!(name("20005;6;td-378;od-6522;oq-6"), "Team Captain Strangifier Chemistry Set");
+        assert_eq!(name("20007;6;od-205;oq-14"), "Collector's Rocket Launcher Chemistry Set");
+        assert_eq!(name("9258;5;td-378"), "Unusual Team Captain Unusualifier");
+        assert_eq!(name("5022;6;c2"), "Mann Co. Supply Crate #2");
+    }
+
+    #[test]
+    fn falls_back_without_schema() {
+        let sku = "378;5;u13;pk104".parse::<SKU>().unwrap();
+
+        assert_eq!(sku.name(&Schema::default()), "Effect #13 Paint Kit #104 Item #378");
+    }
+}
@@ -0,0 +1,490 @@
+//! Parsing item names back into SKUs.
+
+use crate::SKU;
+use crate::error::NameParseError;
+use crate::locale::Word;
+use super::{English, Vocabulary};
+use crate::schema::{ItemSchema, SchemaItem};
+use std::collections::HashMap;
+use tf2_enum::{
+    CraftClass,
+    IntoEnumIterator,
+    KillstreakTier,
+    Killstreaker,
+    Quality,
+    Sheen,
+    Wear,
+};
+
+impl SKU {
+    /// Parses a full item name, as produced by [`SKU::name`], into a SKU. Matching is case
+    /// insensitive.
+    ///
+    /// When there is no quality in the name, the quality is Unusual for items with an effect,
+    /// Decorated Weapon for items with a war paint, or Unique otherwise. Items sharing a name
+    /// are narrowed by quality (stock weapons only exist in Normal quality) and crate series.
+    ///
+    /// # Errors
+    /// Returns [`NameParseError::Ambiguous`] with every candidate if the name still matches more
+    /// than one item.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema, SchemaItem};
+    /// use tf2_enum::Quality;
+    ///
+    /// let mut schema = Schema::default();
+    ///
+    /// schema.items.insert(378, SchemaItem {
+    ///     defindex: 378,
+    ///     name: "The Team Captain".into(),
+    ///     item_name: "Team Captain".into(),
+    ///     proper_name: true,
+    ///     item_class: "tf_wearable".into(),
+    ///     item_quality: Quality::Unique,
+    ///     craft_class: None,
+    ///     item_slot: None,
+    ///     tool_type: None,
+    ///     crate_series: None,
+    ///     paint_kit: None,
+    ///     capabilities: Default::default(),
+    /// });
+    /// schema.particles.insert(13, "Burning Flames".into());
+    ///
+    /// let sku = SKU::from_name("Burning Flames Team Captain", &schema).unwrap();
+    ///
+    /// assert_eq!(sku.to_string(), "378;5;u13");
+    /// ```
+    pub fn from_name<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+    ) -> Result<SKU, NameParseError> {
+        SKU::from_name_in(name, schema, &[&English])
+    }
+
+    /// Parses a full item name using the words from any of `vocabularies`. Names from the schema
+    /// are resolved with the first.
+    pub(crate) fn from_name_in<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+        vocabularies: &[&dyn Vocabulary],
+    ) -> Result<SKU, NameParseError> {
+        let mut skus = SKU::candidates_in(name, schema, vocabularies)?;
+
+        if skus.len() == 1 {
+            Ok(skus.remove(0))
+        } else {
+            Err(NameParseError::Ambiguous(skus))
+        }
+    }
+
+    /// Parses a full item name into every SKU it could be after narrowing.
+    pub(crate) fn candidates_in<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+        vocabularies: &[&dyn Vocabulary],
+    ) -> Result<Vec<SKU>, NameParseError> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(NameParseError::Empty);
+        }
+
+        let names = Names::new(schema, vocabularies);
+        let (rest, number) = strip_number(name);
+        let (mut rest, wear) = names.strip_wear(rest);
+        let mut prefixes = Vec::new();
+        let cores = loop {
+            let cores = names.cores(rest);
+
+            if !cores.is_empty() {
+                break cores;
+            }
+
+            let (prefix, remainder) = names.strip_prefix(rest)
+                .ok_or_else(|| NameParseError::UnknownItem(rest.to_string()))?;
+
+            prefixes.push(prefix);
+            rest = remainder;
+        };
+        let mut base = SKU {
+            wear,
+            ..SKU::default()
+        };
+        let mut qualities = Vec::new();
+
+        for prefix in prefixes {
+            match prefix {
+                Prefix::NonCraftable => base.craftable = false,
+                Prefix::Quality(quality) => qualities.push(quality),
+                Prefix::Particle(particle) => base.particle = Some(particle),
+                Prefix::KillstreakTier(killstreak_tier) => base.killstreak_tier = Some(killstreak_tier),
+                Prefix::Sheen(sheen) => base.sheen = Some(sheen),
+                Prefix::Killstreaker(killstreaker) => base.killstreaker = Some(killstreaker),
+                Prefix::Wear(wear) => base.wear = Some(wear),
+                Prefix::Festivized => base.festivized = true,
+                Prefix::Australium => base.australium = true,
+                Prefix::The => {},
+            }
+        }
+
+        let quality = match qualities.as_slice() {
+            [] => None,
+            // An elevated quality e.g. "Strange Unusual".
+            [Quality::Strange, quality, ..] => {
+                base.strange = true;
+                Some(*quality)
+            },
+            [quality, ..] => Some(*quality),
+        };
+        let mut skus = cores
+            .into_iter()
+            .map(|core| core.to_sku(base, quality, number))
+            .collect::<Vec<_>>();
+
+        narrow(&mut skus, schema, number);
+        skus.dedup();
+
+        Ok(skus)
+    }
+}
+
+/// Narrows candidates sharing a name. Stock items only exist in Normal quality, and upgradeable
+/// items are never Normal quality. Items with a paint kit of their own are named the same as
+/// their base item, which is preferred. Crates are narrowed by their series.
+pub(crate) fn narrow<S: ItemSchema + ?Sized>(skus: &mut Vec<SKU>, schema: &S, number: Option<u32>) {
+    let item_quality = |sku: &SKU| schema.sku_item(sku).map(|item| item.item_quality);
+
+    retain_if_any(skus, |sku| schema.sku_item(sku).is_some_and(|item| item.paint_kit.is_none()));
+
+    retain_if_any(skus, |sku| {
+        let is_stock = item_quality(sku) == Some(Quality::Normal);
+
+        is_stock == (sku.quality == Quality::Normal)
+    });
+
+    if let Some(number) = number {
+        retain_if_any(skus, |sku| {
+            schema.sku_item(sku).and_then(|item| item.crate_series) == Some(number)
+        });
+    }
+}
+
+/// Retains the elements matching `f`, unless none match.
+fn retain_if_any<T, F: Fn(&T) -> bool>(values: &mut Vec<T>, f: F) {
+    if values.iter().any(&f) {
+        values.retain(f);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Prefix {
+    NonCraftable,
+    Quality(Quality),
+    Particle(u32),
+    KillstreakTier(KillstreakTier),
+    Sheen(Sheen),
+    Killstreaker(Killstreaker),
+    Wear(Wear),
+    Festivized,
+    Australium,
+    The,
+}
+
+/// One way of reading the end of a name as an item.
+#[derive(Debug, Clone, Copy)]
+struct Core<'a> {
+    item: &'a SchemaItem,
+    skin: Option<u32>,
+    target: Option<&'a SchemaItem>,
+    output: Option<&'a SchemaItem>,
+}
+
+impl Core<'_> {
+    fn to_sku(self, mut sku: SKU, quality: Option<Quality>, number: Option<u32>) -> SKU {
+        sku.defindex = self.item.defindex as i32;
+        sku.skin = self.skin;
+        sku.target_defindex = self.target.map(|item| item.defindex);
+        sku.output_defindex = self.output.map(|item| item.defindex);
+        sku.quality = quality.unwrap_or(if sku.particle.is_some() {
+            Quality::Unusual
+        } else if sku.skin.is_some() {
+            Quality::DecoratedWeapon
+        } else {
+            Quality::Unique
+        });
+
+        // The quality in the name of a chemistry set is the quality of its output.
+        if sku.output_defindex.is_some() {
+            sku.output_quality = Some(sku.quality);
+            sku.quality = Quality::Unique;
+        }
+
+        if self.item.crate_series.is_some() || self.item.craft_class == Some(CraftClass::SupplyCrate) {
+            sku.crate_number = number;
+        } else {
+            sku.craft_number = number;
+        }
+
+        sku
+    }
+}
+
+/// Lookup tables for the names in a schema. Names are compared in ASCII lowercase, which keeps
+/// byte offsets the same as in the original name.
+struct Names<'a> {
+    items: HashMap<String, Vec<&'a SchemaItem>>,
+    paint_kits: HashMap<String, u32>,
+    /// Prefixes sorted longest first.
+    prefixes: Vec<(String, Prefix)>,
+    /// Wears as they appear at the end of a name e.g. " (field-tested)".
+    wears: Vec<(String, Wear)>,
+}
+
+impl<'a> Names<'a> {
+    fn new<S: ItemSchema + ?Sized>(schema: &'a S, vocabularies: &[&dyn Vocabulary]) -> Self {
+        let resolve = |name| vocabularies.first().map_or(name, |vocabulary| vocabulary.resolve(name));
+        let mut items: HashMap<String, Vec<&SchemaItem>> = HashMap::new();
+        let mut prefixes = vec![("Uncraftable".to_string(), Prefix::NonCraftable)];
+        let mut wears = Vec::new();
+
+        for item in schema.items() {
+            items.entry(resolve(&item.item_name).to_ascii_lowercase()).or_default().push(item);
+        }
+
+        for vocabulary in vocabularies {
+            let word = |w| vocabulary.word(w).into_owned();
+
+            prefixes.extend([
+                (word(Word::NonCraftable), Prefix::NonCraftable),
+                (word(Word::Festivized), Prefix::Festivized),
+                (word(Word::Australium), Prefix::Australium),
+                (word(Word::The), Prefix::The),
+            ]);
+            prefixes.extend(Quality::iter().map(|quality| (word(Word::Quality(quality)), Prefix::Quality(quality))));
+            prefixes.extend(schema.particles().map(|(id, name)| {
+                let name = vocabulary.particle_name(id).unwrap_or_else(|| resolve(name));
+
+                (name.to_string(), Prefix::Particle(id))
+            }));
+            prefixes.extend(KillstreakTier::iter().map(|tier| (word(Word::KillstreakTier(tier)), Prefix::KillstreakTier(tier))));
+            prefixes.extend(Sheen::iter().map(|sheen| (word(Word::Sheen(sheen)), Prefix::Sheen(sheen))));
+            prefixes.extend(Killstreaker::iter().map(|killstreaker| (word(Word::Killstreaker(killstreaker)), Prefix::Killstreaker(killstreaker))));
+
+            for wear in Wear::iter() {
+                let name = word(Word::Wear(wear));
+
+                if vocabulary.wear_first() {
+                    prefixes.push((name.clone(), Prefix::Wear(wear)));
+                }
+
+                wears.push((format!(" ({name})").to_ascii_lowercase(), wear));
+            }
+        }
+
+        for (name, _) in &mut prefixes {
+            name.make_ascii_lowercase();
+        }
+
+        // Stable, so earlier kinds win between prefixes of the same length.
+        prefixes.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+        Self {
+            items,
+            paint_kits: schema.paint_kits()
+                .map(|(id, name)| (resolve(name).to_ascii_lowercase(), id))
+                .collect(),
+            prefixes,
+            wears,
+        }
+    }
+
+    /// Strips a trailing wear e.g. " (Field-Tested)".
+    fn strip_wear<'b>(&self, name: &'b str) -> (&'b str, Option<Wear>) {
+        let lowercase = name.to_ascii_lowercase();
+
+        self.wears
+            .iter()
+            .find(|(suffix, _)| lowercase.ends_with(suffix.as_str()))
+            .map(|(suffix, wear)| (&name[..name.len() - suffix.len()], Some(*wear)))
+            .unwrap_or((name, None))
+    }
+
+    /// Strips the longest known prefix followed by more of the name.
+    fn strip_prefix<'b>(&self, name: &'b str) -> Option<(Prefix, &'b str)> {
+        let lowercase = name.to_ascii_lowercase();
+
+        self.prefixes.iter().find_map(|(prefix, kind)| {
+            let rest = lowercase.strip_prefix(prefix.as_str())?.strip_prefix(' ')?;
+
+            Some((*kind, &name[name.len() - rest.len()..]))
+        })
+    }
+
+    /// Gets the items with a name. Upgradeable items are preferred over stock items.
+    fn lookup(&self, name: &str) -> Vec<&'a SchemaItem> {
+        let mut items = self.items.get(&name.to_ascii_lowercase()).cloned().unwrap_or_default();
+
+        retain_if_any(&mut items, |item| item.item_quality != Quality::Normal);
+        items
+    }
+
+    /// Reads the end of a name as an item, with whatever comes before the item name being its
+    /// target, output or war paint. The longest item name that can be read this way is used.
+    fn cores(&self, name: &str) -> Vec<Core<'a>> {
+        for start in word_starts(name) {
+            let middle = name[..start].trim_end();
+            let cores = self.items.get(&name[start..].to_ascii_lowercase())
+                .into_iter()
+                .flatten()
+                .flat_map(|item| self.read_middle(item, middle))
+                .collect::<Vec<_>>();
+
+            if !cores.is_empty() {
+                return cores;
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn read_middle(&self, item: &'a SchemaItem, middle: &str) -> Vec<Core<'a>> {
+        let core = Core {
+            item,
+            skin: None,
+            target: None,
+            output: None,
+        };
+
+        if middle.is_empty() {
+            return vec![core];
+        }
+
+        match item.tool_type.as_deref() {
+            Some("killstreakifier" | "strangifier" | "unusualifier") => self.lookup(middle)
+                .into_iter()
+                .map(|target| Core {
+                    target: Some(target),
+                    ..core
+                })
+                .collect(),
+            // Fabricators and chemistry sets are named "[target] <output>".
+            Some("fabricator") => {
+                for start in word_starts(middle) {
+                    let outputs = self.lookup(&middle[start..]);
+                    let targets = if start == 0 {
+                        vec![None]
+                    } else {
+                        self.lookup(middle[..start].trim_end()).into_iter().map(Some).collect()
+                    };
+                    let cores = targets
+                        .iter()
+                        .flat_map(|target| outputs.iter().map(move |output| Core {
+                            target: *target,
+                            output: Some(*output),
+                            ..core
+                        }))
+                        .collect::<Vec<_>>();
+
+                    if !cores.is_empty() {
+                        return cores;
+                    }
+                }
+
+                Vec::new()
+            },
+            _ => self.paint_kits.get(&middle.to_ascii_lowercase())
+                .map(|skin| Core {
+                    skin: Some(*skin),
+                    ..core
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Gets the byte offsets where each word in a name starts.
+fn word_starts(name: &str) -> impl Iterator<Item = usize> + '_ {
+    std::iter::once(0).chain(name.match_indices(' ').map(|(i, _)| i + 1))
+}
+
+/// Strips a trailing craft or crate number e.g. " #42".
+fn strip_number(name: &str) -> (&str, Option<u32>) {
+    name.rsplit_once(" #")
+        .and_then(|(rest, number)| Some((rest, Some(number.parse().ok()?))))
+        .unwrap_or((name, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn parse(name: &str) -> Result<String, NameParseError> {
+        SKU::from_name(name, &schema()).map(|sku| sku.to_string())
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!(parse("Non-Craftable Strange Specialized Killstreak Festivized Flame Thrower").unwrap(), "208;11;uncraftable;kt-2;festive");
+        assert_eq!(parse("Burning Flames Team Captain").unwrap(), "378;5;u13");
+        assert_eq!(parse("burning flames team captain").unwrap(), "378;5;u13");
+        assert_eq!(parse("The Team Captain #42").unwrap(), "378;6;n42");
+        assert_eq!(parse("Warhawk Rocket Launcher (Factory New)").unwrap(), "205;15;w1;pk102");
+    }
+
+    #[test]
+    fn narrows_by_quality_and_series() {
+        assert_eq!(parse("Rocket Launcher").unwrap(), "205;6");
+        assert_eq!(parse("Normal Rocket Launcher").unwrap(), "18;0");
+        assert_eq!(parse("Shotgun Kit").unwrap(), "6527;6;td-199");
+        assert_eq!(parse("Mann Co. Supply Crate #2").unwrap(), "5041;6;c2");
+    }
+
+    #[test]
+    fn returns_ambiguous_candidates() {
+        let candidates = |name| match parse(name) {
+            Err(NameParseError::Ambiguous(skus)) => skus.iter().map(|sku| sku.to_string()).collect::<Vec<_>>(),
+            result => panic!("Expected ambiguous result, got {result:?}"),
+        };
+
+        assert_eq!(candidates("Normal Shotgun"), vec!["10;0", "11;0"]);
+        assert_eq!(candidates("Mann Co. Supply Crate"), vec!["5022;6", "5041;6"]);
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert_eq!(parse(" "), Err(NameParseError::Empty));
+        assert_eq!(parse("Strange Unknown Hat"), Err(NameParseError::UnknownItem("Unknown Hat".into())));
+    }
+
+    #[test]
+    fn round_trips_names() {
+        let schema = schema();
+
+        for s in [
+            "205;11;kt-3;ks-1;ke-2008;festive;australium;w3",
+            "378;5;u13;strange",
+            "378;5",
+            "378;1",
+            "205;11;w2;pk104",
+            "16102;15;w3;pk102",
+            "6527;6;kt-2;td-200",
+            "20002;6;kt-3;td-205;od-6527;oq-6",
+            "20005;6;td-378;od-6522;oq-6",
+            "6522;6;td-378",
+            "9258;5;td-378",
+        ] {
+            let sku = s.parse::<SKU>().unwrap();
+            let name = sku.name(&schema);
+
+            match SKU::from_name(&name, &schema) {
+                Ok(parsed) => assert_eq!(parsed, sku, "{name}"),
+                // Both chemistry sets share a name.
+                Err(NameParseError::Ambiguous(candidates)) => assert!(candidates.contains(&sku), "{name}"),
+                Err(error) => panic!("{name}: {error}"),
+            }
+        }
+    }
+}
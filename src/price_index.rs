@@ -0,0 +1,196 @@
+//! backpack.tf price indexes.
+//!
+//! backpack.tf prices an item by its defindex, quality, tradability, craftability and a price
+//! index, which holds what the rest leaves out:
+//!
+//! | Item | Price index |
+//! |---|---|
+//! | Unusual | The effect e.g. `13` |
+//! | Crate | The series e.g. `1` |
+//! | Strangifier or Unusualifier | The target e.g. `205` |
+//! | Killstreak Kit | The tier and target e.g. `3-205` |
+//! | Chemistry Set | The output, output quality and target e.g. `20000-14-205` |
+
+use crate::SKU;
+use crate::error::ItemError;
+use crate::schema::ItemSchema;
+use tf2_enum::{CraftClass, Quality};
+
+impl SKU {
+    /// Gets the backpack.tf price index of the item, if it has one.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    ///
+    /// assert_eq!("378;5;u13".parse::<SKU>().unwrap().price_index().as_deref(), Some("13"));
+    /// assert_eq!("6527;6;kt-3;td-205".parse::<SKU>().unwrap().price_index().as_deref(), Some("3-205"));
+    /// assert_eq!("205;11".parse::<SKU>().unwrap().price_index(), None);
+    /// ```
+    pub fn price_index(&self) -> Option<String> {
+        if let Some(output_defindex) = self.output_defindex {
+            let output_quality = self.output_quality.unwrap_or(Quality::Unique) as u32;
+
+            return Some(match self.target_defindex {
+                Some(target_defindex) => format!("{output_defindex}-{output_quality}-{target_defindex}"),
+                None => format!("{output_defindex}-{output_quality}"),
+            });
+        }
+
+        match (self.killstreak_tier, self.target_defindex) {
+            (Some(killstreak_tier), Some(target_defindex)) => {
+                Some(format!("{}-{target_defindex}", killstreak_tier as u32))
+            },
+            (None, Some(target_defindex)) => Some(target_defindex.to_string()),
+            _ => self.particle.or(self.crate_number).map(|number| number.to_string()),
+        }
+    }
+
+    /// Builds a SKU from the parts backpack.tf prices an item by. An empty price index means the
+    /// item has none.
+    ///
+    /// A price index holding a single number can be an effect, a crate series or a target, which
+    /// is told apart by the item's tool type and craft class in the schema.
+    ///
+    /// # Errors
+    /// Returns [`ItemError::NotTradable`] if the item isn't tradable, as a SKU would name the
+    /// tradable item, [`ItemError::InvalidDefindex`] if the defindex doesn't fit in a SKU and
+    /// [`ItemError::InvalidPriceIndex`] if the price index isn't valid for the item.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::error::ItemError;
+    /// use tf2_enum::Quality;
+    ///
+    /// let sku = SKU::from_price_index(378, Quality::Unusual, true, true, "13", &Schema::default()).unwrap();
+    ///
+    /// assert_eq!(sku.to_string(), "378;5;u13");
+    /// assert_eq!(
+    ///     SKU::from_price_index(378, Quality::Unusual, false, true, "13", &Schema::default()),
+    ///     Err(ItemError::NotTradable),
+    /// );
+    /// ```
+    pub fn from_price_index<S: ItemSchema + ?Sized>(
+        defindex: u32,
+        quality: Quality,
+        tradable: bool,
+        craftable: bool,
+        price_index: &str,
+        schema: &S,
+    ) -> Result<Self, ItemError> {
+        if !tradable {
+            return Err(ItemError::NotTradable);
+        }
+
+        let invalid = || ItemError::InvalidPriceIndex(price_index.to_string());
+        let numbers = parse_price_index(price_index).ok_or_else(invalid)?;
+        let mut sku = SKU::new(
+            i32::try_from(defindex).map_err(|_| ItemError::InvalidDefindex(defindex))?,
+            quality,
+        );
+
+        sku.craftable = craftable;
+        apply_price_index(&mut sku, schema, &numbers).ok_or_else(invalid)?;
+
+        Ok(sku)
+    }
+}
+
+/// Splits a price index into its numbers. Returns `None` if any part isn't a number.
+pub(crate) fn parse_price_index(price_index: &str) -> Option<Vec<u32>> {
+    if price_index.is_empty() {
+        return Some(Vec::new());
+    }
+
+    price_index
+        .split('-')
+        .map(|number| number.parse::<u32>().ok())
+        .collect()
+}
+
+/// Sets the attributes held by the numbers of a price index. Returns `None` if the numbers
+/// don't fit the item.
+pub(crate) fn apply_price_index<S: ItemSchema + ?Sized>(
+    sku: &mut SKU,
+    schema: &S,
+    numbers: &[u32],
+) -> Option<()> {
+    let item = schema.sku_item(sku);
+    let tool_type = item.and_then(|item| item.tool_type.as_deref());
+    let is_crate = item.is_some_and(|item| {
+        item.crate_series.is_some() || item.craft_class == Some(CraftClass::SupplyCrate)
+    });
+
+    match (numbers, tool_type) {
+        ([], _) => {},
+        ([output, output_quality, rest @ ..], Some("fabricator")) if rest.len() <= 1 => {
+            sku.output_defindex = Some(*output);
+            sku.output_quality = Some(Quality::try_from(*output_quality).ok()?);
+            sku.target_defindex = rest.first().copied();
+        },
+        ([killstreak_tier, target], Some("killstreakifier")) => {
+            sku.killstreak_tier = Some((*killstreak_tier).try_into().ok()?);
+            sku.target_defindex = Some(*target);
+        },
+        ([target], Some("killstreakifier" | "strangifier" | "unusualifier")) => {
+            sku.target_defindex = Some(*target);
+        },
+        ([number], _) if is_crate => sku.crate_number = Some(*number),
+        ([particle], _) => sku.particle = Some(*particle),
+        _ => return None,
+    }
+
+    // A chemistry set's quality is its output's quality in a name.
+    if sku.output_quality.is_some() && sku.output_defindex.is_some() {
+        sku.quality = Quality::Unique;
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn from_price_index(defindex: u32, quality: Quality, price_index: &str) -> Result<String, ItemError> {
+        SKU::from_price_index(defindex, quality, true, true, price_index, &schema()).map(|sku| sku.to_string())
+    }
+
+    #[test]
+    fn reads_price_indexes() {
+        assert_eq!(from_price_index(378, Quality::Unusual, "13"), Ok("378;5;u13".into()));
+        assert_eq!(from_price_index(5022, Quality::Unique, "1"), Ok("5022;6;c1".into()));
+        assert_eq!(from_price_index(6522, Quality::Unique, "205"), Ok("6522;6;td-205".into()));
+        assert_eq!(from_price_index(6527, Quality::Unique, "3-205"), Ok("6527;6;kt-3;td-205".into()));
+        assert_eq!(from_price_index(20005, Quality::Unique, "6522-6-378"), Ok("20005;6;td-378;od-6522;oq-6".into()));
+        assert_eq!(from_price_index(205, Quality::Strange, ""), Ok("205;11".into()));
+    }
+
+    #[test]
+    fn round_trips_price_indexes() {
+        for sku in ["378;5;u13", "5041;6;c2", "6522;6;td-205", "6527;6;kt-2;td-205", "20005;6;td-378;od-6522;oq-6", "205;11;uncraftable"] {
+            let sku = sku.parse::<SKU>().unwrap();
+            let price_index = sku.price_index().unwrap_or_default();
+            let parsed = SKU::from_price_index(sku.defindex as u32, sku.quality, true, sku.craftable, &price_index, &schema());
+
+            assert_eq!(parsed, Ok(sku));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_price_indexes() {
+        for price_index in ["x", "1-", "1-2-3-4", "4-205"] {
+            assert_eq!(
+                from_price_index(6527, Quality::Unique, price_index),
+                Err(ItemError::InvalidPriceIndex(price_index.into())),
+            );
+        }
+
+        assert_eq!(
+            SKU::from_price_index(205, Quality::Strange, false, true, "", &schema()),
+            Err(ItemError::NotTradable),
+        );
+    }
+}
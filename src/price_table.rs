@@ -0,0 +1,88 @@
+//! Buy and sell prices indexed by SKU.
+
+use crate::SKU;
+use crate::currencies::Currencies;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The buying and selling price of an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ItemPrice {
+    /// What the item is bought for.
+    pub buy: Currencies,
+    /// What the item is sold for.
+    pub sell: Currencies,
+}
+
+/// Prices indexed by SKU.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::SKU;
+/// use tf2_sku::currencies::Currencies;
+/// use tf2_sku::price_table::{ItemPrice, PriceTable};
+///
+/// let key = "5021;6".parse::<SKU>().unwrap();
+/// let price = ItemPrice {
+///     buy: Currencies::new(0, 1086),
+///     sell: Currencies::new(0, 1088),
+/// };
+/// let table = [(key, price)].into_iter().collect::<PriceTable>();
+///
+/// assert_eq!(table.get(&key), Some(&price));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PriceTable {
+    prices: HashMap<SKU, ItemPrice>,
+}
+
+impl PriceTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the price of a SKU, returning the previous price.
+    pub fn insert(&mut self, sku: SKU, price: ItemPrice) -> Option<ItemPrice> {
+        self.prices.insert(sku, price)
+    }
+
+    /// Gets the price of a SKU.
+    pub fn get(&self, sku: &SKU) -> Option<&ItemPrice> {
+        self.prices.get(sku)
+    }
+
+    /// Removes the price of a SKU.
+    pub fn remove(&mut self, sku: &SKU) -> Option<ItemPrice> {
+        self.prices.remove(sku)
+    }
+
+    /// Gets the number of prices.
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    /// Checks whether there are no prices.
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// Iterates over the SKUs and their prices, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&SKU, &ItemPrice)> + '_ {
+        self.prices.iter()
+    }
+}
+
+impl FromIterator<(SKU, ItemPrice)> for PriceTable {
+    fn from_iter<I: IntoIterator<Item = (SKU, ItemPrice)>>(iter: I) -> Self {
+        Self {
+            prices: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(SKU, ItemPrice)> for PriceTable {
+    fn extend<I: IntoIterator<Item = (SKU, ItemPrice)>>(&mut self, iter: I) {
+        self.prices.extend(iter);
+    }
+}
@@ -0,0 +1,231 @@
+//! Models for a prices.tf price feed, keyed by SKU.
+//!
+//! The feed gives prices in keys plus half scrap, the same unit [`Currencies`] counts metal in,
+//! along with the key price in half scrap when the price was made.
+
+use crate::SKU;
+use crate::currencies::Currencies;
+use crate::price_table::{ItemPrice, PriceTable};
+use serde::{Deserialize, Serialize};
+
+/// The price of an item in the feed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceFeedItem {
+    /// The SKU.
+    pub sku: SKU,
+    /// The metal part of the buying price, in half scrap.
+    pub buy_half_scrap: i64,
+    /// The keys part of the buying price.
+    pub buy_keys: i64,
+    /// The price of a key in half scrap when the buying price was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buy_key_half_scrap: Option<i64>,
+    /// The metal part of the selling price, in half scrap.
+    pub sell_half_scrap: i64,
+    /// The keys part of the selling price.
+    pub sell_keys: i64,
+    /// The price of a key in half scrap when the selling price was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sell_key_half_scrap: Option<i64>,
+    /// When the item was first priced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// When the price was last updated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl PriceFeedItem {
+    /// Gets the buying price.
+    pub fn buy(&self) -> Currencies {
+        Currencies::new(self.buy_keys, self.buy_half_scrap)
+    }
+
+    /// Gets the selling price.
+    pub fn sell(&self) -> Currencies {
+        Currencies::new(self.sell_keys, self.sell_half_scrap)
+    }
+
+    /// Gets the buying and selling price.
+    pub fn price(&self) -> ItemPrice {
+        ItemPrice {
+            buy: self.buy(),
+            sell: self.sell(),
+        }
+    }
+}
+
+/// A page of prices, as returned by the feed's paginated endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PricesPage {
+    /// The prices on the page.
+    pub items: Vec<PriceFeedItem>,
+    /// The position of the page.
+    pub meta: PageMeta,
+}
+
+/// The position of a page of prices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageMeta {
+    /// The page number, starting from 1.
+    pub current_page: u32,
+    /// The number of pages.
+    pub total_pages: u32,
+    /// The number of prices on the page.
+    #[serde(default)]
+    pub item_count: u32,
+    /// The most prices a page holds.
+    #[serde(default)]
+    pub items_per_page: u32,
+    /// The total number of prices across all pages.
+    #[serde(default)]
+    pub total_items: u32,
+}
+
+impl PageMeta {
+    /// Checks whether this is the last page.
+    pub fn is_last_page(&self) -> bool {
+        self.current_page >= self.total_pages
+    }
+}
+
+impl PriceTable {
+    /// Builds a table from feed items. Later items replace earlier ones with the same SKU.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::SKU;
+    /// use tf2_sku::currencies::Currencies;
+    /// use tf2_sku::price_table::PriceTable;
+    /// use tf2_sku::prices_tf::PriceFeedItem;
+    ///
+    /// let json = r#"[{
+    ///     "sku": "5021;6",
+    ///     "buyHalfScrap": 1086,
+    ///     "buyKeys": 0,
+    ///     "sellHalfScrap": 1088,
+    ///     "sellKeys": 0
+    /// }]"#;
+    /// let items = serde_json::from_str::<Vec<PriceFeedItem>>(json).unwrap();
+    /// let table = PriceTable::from_feed(&items);
+    /// let price = table.get(&"5021;6".parse::<SKU>().unwrap()).unwrap();
+    ///
+    /// assert_eq!(price.sell, Currencies::new(0, 1088));
+    /// ```
+    pub fn from_feed<'a, I>(items: I) -> Self
+    where
+        I: IntoIterator<Item = &'a PriceFeedItem>,
+    {
+        items
+            .into_iter()
+            .map(|item| (item.sku, item.price()))
+            .collect()
+    }
+
+    /// Builds a table from every page of the feed. `fetch` gets a page by its number, starting
+    /// from 1, and pages are fetched until the last page or an empty page.
+    ///
+    /// # Errors
+    /// Returns the first error from `fetch`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::price_table::PriceTable;
+    /// use tf2_sku::prices_tf::PricesPage;
+    ///
+    /// let table = PriceTable::from_pages(|page| {
+    ///     let json = format!(r#"{{
+    ///         "items": [{{ "sku": "5021;6", "buyHalfScrap": 1086, "buyKeys": 0, "sellHalfScrap": 1088, "sellKeys": 0 }}],
+    ///         "meta": {{ "currentPage": {page}, "totalPages": 1 }}
+    ///     }}"#);
+    ///
+    ///     serde_json::from_str::<PricesPage>(&json)
+    /// }).unwrap();
+    ///
+    /// assert_eq!(table.len(), 1);
+    /// ```
+    pub fn from_pages<F, E>(mut fetch: F) -> Result<Self, E>
+    where
+        F: FnMut(u32) -> Result<PricesPage, E>,
+    {
+        let mut table = Self::new();
+
+        for page in 1.. {
+            let page = fetch(page)?;
+
+            table.extend(page.items.iter().map(|item| (item.sku, item.price())));
+
+            if page.items.is_empty() || page.meta.is_last_page() {
+                break;
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read<T: serde::de::DeserializeOwned>(path: &str) -> T {
+        let json = std::fs::read_to_string(path).unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn sku(sku: &str) -> SKU {
+        sku.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_saved_feeds() {
+        let items = read::<Vec<PriceFeedItem>>("tests/fixtures/prices_tf/prices.json");
+        let table = PriceTable::from_feed(&items);
+        let key = table.get(&sku("5021;6")).unwrap();
+
+        assert_eq!(table.len(), 4);
+        assert_eq!(key.buy, Currencies::new(0, 60 * Currencies::ONE_REF + 3 * Currencies::ONE_SCRAP));
+        assert_eq!(key.sell, Currencies::new(0, 60 * Currencies::ONE_REF + 4 * Currencies::ONE_SCRAP));
+        assert_eq!(items[1].buy_key_half_scrap, Some(1086));
+        assert_eq!(table.get(&sku("378;5;u13")).unwrap().sell, Currencies::new(250, 9));
+        assert_eq!(table.get(&sku("205;6")).unwrap().buy, Currencies::new(0, 1));
+        assert_eq!(table.get(&sku("378;6")), None);
+    }
+
+    #[test]
+    fn reads_paginated_responses() {
+        let mut requested = Vec::new();
+        // Stands in for the paginated endpoint, serving the saved pages.
+        let table = PriceTable::from_pages(|page| {
+            requested.push(page);
+            std::fs::read_to_string(format!("tests/fixtures/prices_tf/page_{page}.json"))
+                .map(|json| serde_json::from_str::<PricesPage>(&json).unwrap())
+        }).unwrap();
+        let first = read::<PricesPage>("tests/fixtures/prices_tf/page_1.json");
+
+        assert_eq!(requested, [1, 2]);
+        assert_eq!(first.meta.items_per_page, 2);
+        assert_eq!(table.len(), first.meta.total_items as usize);
+        assert_eq!(table.get(&sku("205;11;kt-3")).unwrap().sell, Currencies::new(1, 2 * Currencies::ONE_REF));
+    }
+
+    #[test]
+    fn stops_at_fetch_errors() {
+        let result = PriceTable::from_pages(|page| match page {
+            1 => Ok(read::<PricesPage>("tests/fixtures/prices_tf/page_1.json")),
+            _ => Err("server error"),
+        });
+
+        assert_eq!(result, Err("server error"));
+    }
+
+    #[test]
+    fn rejects_invalid_skus() {
+        let json = r#"[{ "sku": "not a sku", "buyHalfScrap": 0, "buyKeys": 0, "sellHalfScrap": 0, "sellKeys": 0 }]"#;
+
+        assert!(serde_json::from_str::<Vec<PriceFeedItem>>(json).is_err());
+    }
+}
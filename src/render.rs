@@ -0,0 +1,289 @@
+//! Rendering SKUs with TF2 quality colors, for terminals (ANSI escape codes) and web pages (HTML).
+//!
+//! HTML output uses these classes so it can be styled further:
+//!
+//! | Class | Element |
+//! |---|---|
+//! | `sku` | The whole SKU |
+//! | `sku-label` | The name or summary, colored by quality |
+//! | `sku-uncraftable` | The marker for non-craftable items |
+//! | `sku-paint` | The paint swatch |
+//! | `sku-badge sku-spell` | A spell |
+//! | `sku-badge sku-part` | A strange part |
+
+use crate::SKU;
+use crate::schema::ItemSchema;
+use crate::shorthand::Abbreviations;
+use tf2_enum::Colored;
+
+/// The color of spell badges.
+const SPELL_COLOR: u32 = 0x38F3AB;
+/// The color of strange part badges.
+const PART_COLOR: u32 = 0xCF6A32;
+/// The color of the marker for non-craftable items.
+const UNCRAFTABLE_COLOR: u32 = 0x7F7F7F;
+
+/// What the label of a rendered SKU shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Label<'a> {
+    /// The full name e.g. "Strange Professional Killstreak Rocket Launcher".
+    #[default]
+    Name,
+    /// The shorthand name e.g. "Strange Pro KS Rocket Launcher".
+    Shorthand(&'a Abbreviations),
+    /// The SKU string e.g. "205;11;kt-3".
+    Sku,
+}
+
+/// Options for rendering a SKU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions<'a> {
+    /// What the label shows.
+    pub label: Label<'a>,
+    /// Whether to show a swatch for the paint.
+    pub paint: bool,
+    /// Whether to show spells and strange parts as badges.
+    pub badges: bool,
+}
+
+impl Default for RenderOptions<'_> {
+    /// Shows the full name with the paint and badges.
+    fn default() -> Self {
+        Self {
+            label: Label::Name,
+            paint: true,
+            badges: true,
+        }
+    }
+}
+
+/// A part of a rendered SKU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Label {
+        text: String,
+        color: u32,
+    },
+    Uncraftable,
+    Paint {
+        name: String,
+        color: u32,
+    },
+    Badge {
+        class: &'static str,
+        text: String,
+        color: u32,
+    },
+}
+
+impl SKU {
+    /// Renders this SKU with ANSI escape codes for terminals that support 24-bit color.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::render::RenderOptions;
+    ///
+    /// let sku = "205;11".parse::<SKU>().unwrap();
+    /// let rendered = sku.to_ansi(&Schema::default(), &RenderOptions::default());
+    ///
+    /// assert_eq!(rendered, "\x1b[38;2;207;106;50mStrange Item #205\x1b[0m");
+    /// ```
+    pub fn to_ansi<S: ItemSchema + ?Sized>(&self, schema: &S, options: &RenderOptions) -> String {
+        self.segments(schema, options)
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Label {
+                    text,
+                    color,
+                } => format!("{}{text}{RESET}", foreground(color)),
+                Segment::Uncraftable => format!("{}(Non-Craftable){RESET}", foreground(UNCRAFTABLE_COLOR)),
+                Segment::Paint {
+                    name,
+                    color,
+                } => format!("{}  {RESET} {name}", background(color)),
+                Segment::Badge {
+                    text,
+                    color,
+                    ..
+                } => format!("{}[{text}]{RESET}", foreground(color)),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Renders this SKU as HTML spans with inline colors.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::render::RenderOptions;
+    ///
+    /// let sku = "205;11".parse::<SKU>().unwrap();
+    /// let rendered = sku.to_html(&Schema::default(), &RenderOptions::default());
+    ///
+    /// assert_eq!(rendered, r#"<span class="sku"><span class="sku-label" style="color:#CF6A32">Strange Item #205</span></span>"#);
+    /// ```
+    pub fn to_html<S: ItemSchema + ?Sized>(&self, schema: &S, options: &RenderOptions) -> String {
+        let spans = self.segments(schema, options)
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Label {
+                    text,
+                    color,
+                } => format!(
+                    r#"<span class="sku-label" style="color:#{color:06X}">{}</span>"#,
+                    escape(&text),
+                ),
+                Segment::Uncraftable => format!(
+                    r#"<span class="sku-uncraftable" style="color:#{UNCRAFTABLE_COLOR:06X}" title="Non-Craftable">&#8856;</span>"#,
+                ),
+                Segment::Paint {
+                    name,
+                    color,
+                } => format!(
+                    r#"<span class="sku-paint" style="display:inline-block;width:1em;height:1em;background-color:#{color:06X}" title="{}"></span>"#,
+                    escape(&name),
+                ),
+                Segment::Badge {
+                    class,
+                    text,
+                    color,
+                } => format!(
+                    r#"<span class="sku-badge {class}" style="border:1px solid #{color:06X};color:#{color:06X}">{}</span>"#,
+                    escape(&text),
+                ),
+            })
+            .collect::<String>();
+
+        format!(r#"<span class="sku">{spans}</span>"#)
+    }
+
+    fn segments<S: ItemSchema + ?Sized>(&self, schema: &S, options: &RenderOptions) -> Vec<Segment> {
+        let text = match options.label {
+            Label::Name => self.name(schema),
+            Label::Shorthand(abbreviations) => self.shorthand(schema, abbreviations),
+            Label::Sku => self.to_string(),
+        };
+        let mut segments = vec![Segment::Label {
+            text,
+            color: self.quality.color(),
+        }];
+
+        // Names already say "Non-Craftable".
+        if !self.craftable && options.label == Label::Sku {
+            segments.push(Segment::Uncraftable);
+        }
+
+        if options.paint {
+            if let Some(paint) = self.paint {
+                segments.push(Segment::Paint {
+                    name: paint.to_string(),
+                    color: paint.color(),
+                });
+            }
+        }
+
+        if options.badges {
+            segments.extend(self.spells.into_iter().map(|spell| Segment::Badge {
+                class: "sku-spell",
+                text: spell.to_string(),
+                color: SPELL_COLOR,
+            }));
+            segments.extend(self.strange_parts.into_iter().map(|strange_part| Segment::Badge {
+                class: "sku-part",
+                text: strange_part.to_string(),
+                color: PART_COLOR,
+            }));
+        }
+
+        segments
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+fn foreground(color: u32) -> String {
+    let [_, r, g, b] = color.to_be_bytes();
+
+    format!("\x1b[38;2;{r};{g};{b}m")
+}
+
+fn background(color: u32) -> String {
+    let [_, r, g, b] = color.to_be_bytes();
+
+    format!("\x1b[48;2;{r};{g};{b}m")
+}
+
+/// Escapes text for HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    #[test]
+    fn renders_ansi() {
+        let sku = "378;6;p15185211;footprints-2;sp-28".parse::<SKU>().unwrap();
+        let rendered = sku.to_ansi(&schema(), &RenderOptions::default());
+
+        assert!(rendered.starts_with("\x1b[38;2;255;215;0mThe Team Captain\x1b[0m"));
+        // Australium Gold.
+        assert!(rendered.contains("\x1b[48;2;231;181;59m  \x1b[0m"));
+        assert!(rendered.contains("[Headless Horseshoes]"));
+        assert!(rendered.ends_with("[Dominations]\x1b[0m"));
+    }
+
+    #[test]
+    fn marks_uncraftable_skus() {
+        let sku = "205;11;uncraftable".parse::<SKU>().unwrap();
+        let options = RenderOptions {
+            label: Label::Sku,
+            ..Default::default()
+        };
+
+        assert!(sku.to_ansi(&schema(), &options).contains("(Non-Craftable)"));
+        assert!(sku.to_html(&schema(), &options).contains(r#"class="sku-uncraftable""#));
+        // The name already says so.
+        assert!(!sku.to_html(&schema(), &RenderOptions::default()).contains("sku-uncraftable"));
+    }
+
+    #[test]
+    fn renders_html() {
+        let sku = "20007;6;od-205;oq-14;footprints-2".parse::<SKU>().unwrap();
+        let rendered = sku.to_html(&schema(), &RenderOptions::default());
+
+        assert!(rendered.starts_with(r#"<span class="sku"><span class="sku-label" style="color:#FFD700">Collector&#39;s Rocket Launcher Chemistry Set</span>"#));
+        assert!(rendered.contains(r#"<span class="sku-badge sku-spell" style="border:1px solid #38F3AB;color:#38F3AB">Headless Horseshoes</span>"#));
+        assert!(rendered.ends_with("</span></span>"));
+    }
+
+    #[test]
+    fn renders_shorthand() {
+        let abbreviations = Abbreviations::default();
+        let sku = "205;11;kt-3".parse::<SKU>().unwrap();
+        let options = RenderOptions {
+            label: Label::Shorthand(&abbreviations),
+            paint: false,
+            badges: false,
+        };
+
+        assert!(sku.to_ansi(&schema(), &options).contains("Strange Pro KS Rocket Launcher"));
+    }
+}
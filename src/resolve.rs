@@ -0,0 +1,243 @@
+//! Resolving item references of any kind into SKUs.
+
+use crate::SKU;
+use crate::error::{NameParseError, UrlError};
+use crate::schema::ItemSchema;
+use crate::search::ItemSearch;
+use tf2_enum::Quality;
+
+/// The most candidates returned from a fuzzy search.
+const SEARCH_LIMIT: usize = 10;
+
+/// Hosts recognized as links without a scheme.
+const HOSTS: &[&str] = &["backpack.tf", "marketplace.tf", "steamcommunity.com", "www."];
+
+/// The kind of reference detected by [`resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    /// A SKU string e.g. "205;11;kt-3".
+    Sku,
+    /// A bare defindex e.g. "205".
+    Defindex,
+    /// A link supported by [`crate::url::parse`].
+    Url,
+    /// A full item name e.g. "Strange Professional Killstreak Rocket Launcher".
+    Name,
+    /// A Steam Community Market name e.g. "Mann Co. Supply Crate Series #1".
+    MarketHashName,
+    /// Anything else, matched by a fuzzy search e.g. "pro ks rocket launch".
+    Search,
+}
+
+/// The result of [`resolve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// The reference is a single SKU.
+    Resolved {
+        /// The kind of reference.
+        kind: InputKind,
+        /// The SKU.
+        sku: SKU,
+    },
+    /// The reference matches more than one SKU.
+    Candidates {
+        /// The kind of reference.
+        kind: InputKind,
+        /// The matching SKUs, best first.
+        candidates: Vec<SKU>,
+    },
+    /// Nothing matches the reference.
+    Unresolved,
+}
+
+impl Resolution {
+    /// Gets the kind of reference, unless nothing matched.
+    pub fn kind(&self) -> Option<InputKind> {
+        match self {
+            Resolution::Resolved {
+                kind,
+                ..
+            } |
+            Resolution::Candidates {
+                kind,
+                ..
+            } => Some(*kind),
+            Resolution::Unresolved => None,
+        }
+    }
+
+    /// Gets the SKU if the reference resolved to exactly one.
+    pub fn sku(&self) -> Option<&SKU> {
+        match self {
+            Resolution::Resolved {
+                sku,
+                ..
+            } => Some(sku),
+            _ => None,
+        }
+    }
+
+    /// Gets every matching SKU, best first.
+    pub fn candidates(&self) -> &[SKU] {
+        match self {
+            Resolution::Resolved {
+                sku,
+                ..
+            } => std::slice::from_ref(sku),
+            Resolution::Candidates {
+                candidates,
+                ..
+            } => candidates,
+            Resolution::Unresolved => &[],
+        }
+    }
+
+    fn from_skus(kind: InputKind, mut skus: Vec<SKU>) -> Self {
+        match skus.len() {
+            0 => Resolution::Unresolved,
+            1 => Resolution::Resolved {
+                kind,
+                sku: skus.remove(0),
+            },
+            _ => Resolution::Candidates {
+                kind,
+                candidates: skus,
+            },
+        }
+    }
+}
+
+/// Resolves an item reference into a SKU. References are tried as, in order, a link, a bare
+/// defindex, a SKU string, a full item name and a market name, before falling back to a fuzzy
+/// search. A bare defindex missing from the schema resolves to a unique SKU rather than being
+/// searched for.
+///
+/// Each fuzzy search builds an [`ItemSearch`] index, so use one directly when searching many
+/// times. Names are matched as the schema gives them, so localize a schema built from
+/// `items_game.txt` with [`Localization::localize`](crate::locale::Localization::localize) first.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{InputKind, SKU, Schema, resolve};
+///
+/// let resolution = resolve("205;11;kt-3", &Schema::default());
+///
+/// assert_eq!(resolution.kind(), Some(InputKind::Sku));
+/// assert_eq!(resolution.sku(), Some(&"205;11;kt-3".parse::<SKU>().unwrap()));
+/// ```
+pub fn resolve<S: ItemSchema + ?Sized>(input: &str, schema: &S) -> Resolution {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Resolution::Unresolved;
+    }
+
+    if is_url(input) {
+        return match crate::url::parse(input, schema) {
+            Ok(sku) => Resolution::from_skus(InputKind::Url, vec![sku]),
+            Err(UrlError::Name(NameParseError::Ambiguous(skus))) => {
+                Resolution::from_skus(InputKind::Url, skus)
+            },
+            Err(_) => Resolution::Unresolved,
+        };
+    }
+
+    if let Some(defindex) = input.parse::<u32>().ok().filter(|defindex| *defindex <= i32::MAX as u32) {
+        let sku = match schema.item(defindex) {
+            Some(item) => SKU::new(item.defindex as i32, item.item_quality),
+            None => SKU::new(defindex as i32, Quality::Unique),
+        };
+
+        return Resolution::from_skus(InputKind::Defindex, vec![sku]);
+    }
+
+    if input.contains(';') {
+        if let Ok(sku) = input.parse::<SKU>() {
+            return Resolution::from_skus(InputKind::Sku, vec![sku]);
+        }
+    }
+
+    match SKU::from_name(input, schema) {
+        Ok(sku) => return Resolution::from_skus(InputKind::Name, vec![sku]),
+        Err(NameParseError::Ambiguous(skus)) => return Resolution::from_skus(InputKind::Name, skus),
+        Err(_) => {},
+    }
+
+    if let Ok(skus) = SKU::from_market_hash_name(input, schema) {
+        return Resolution::from_skus(InputKind::MarketHashName, skus);
+    }
+
+    let candidates = ItemSearch::new(schema)
+        .search(input, SEARCH_LIMIT)
+        .into_iter()
+        .map(|item_match| item_match.sku)
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        Resolution::Unresolved
+    } else {
+        // Fuzzy matches are never certain, even when there is only one.
+        Resolution::Candidates {
+            kind: InputKind::Search,
+            candidates,
+        }
+    }
+}
+
+/// Checks whether the input looks like a link.
+fn is_url(input: &str) -> bool {
+    let lowercase = input.to_ascii_lowercase();
+
+    lowercase.contains("://") || HOSTS.iter().any(|host| lowercase.starts_with(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn resolved(input: &str) -> (Option<InputKind>, Vec<String>) {
+        let resolution = resolve(input, &schema());
+        let candidates = resolution.candidates().iter().map(|sku| sku.to_string()).collect();
+
+        (resolution.kind(), candidates)
+    }
+
+    #[test]
+    fn detects_input_kinds() {
+        assert_eq!(resolved("205;11;kt-3"), (Some(InputKind::Sku), vec!["205;11;kt-3".into()]));
+        assert_eq!(resolved("205"), (Some(InputKind::Defindex), vec!["205;6".into()]));
+        assert_eq!(resolved("5021"), (Some(InputKind::Defindex), vec!["5021;6".into()]));
+        assert_eq!(resolved("backpack.tf/stats/Strange/Rocket%20Launcher/Tradable/Craftable"), (Some(InputKind::Url), vec!["205;11".into()]));
+        assert_eq!(resolved("Burning Flames Team Captain"), (Some(InputKind::Name), vec!["378;5;u13".into()]));
+        assert_eq!(resolved("Mann Co. Supply Crate Series #2"), (Some(InputKind::MarketHashName), vec!["5041;6;c2".into()]));
+    }
+
+    #[test]
+    fn resolves_unknown_defindexes() {
+        assert_eq!(resolve("30000", &schema()), Resolution::Resolved {
+            kind: InputKind::Defindex,
+            sku: SKU::new(30000, Quality::Unique),
+        });
+    }
+
+    #[test]
+    fn falls_back_to_search() {
+        let resolution = resolve("pro ks rocket launch", &schema());
+
+        assert_eq!(resolution.kind(), Some(InputKind::Search));
+        assert_eq!(resolution.sku(), None);
+        assert_eq!(resolution.candidates()[0].to_string(), "205;6;kt-3");
+    }
+
+    #[test]
+    fn returns_candidates_and_unresolved() {
+        let (kind, candidates) = resolved("Chemistry Set");
+
+        assert_eq!(kind, Some(InputKind::Name));
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(resolve("", &schema()), Resolution::Unresolved);
+        assert_eq!(resolve("https://example.com/205", &schema()), Resolution::Unresolved);
+        assert_eq!(resolve("zzzzqqqq", &schema()), Resolution::Unresolved);
+    }
+}
@@ -0,0 +1,265 @@
+//! Differences between two schema snapshots.
+
+use super::ItemSchema;
+use crate::SKU;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// An entry identified by a numeric ID, e.g. an item by its defindex.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Entry {
+    /// The ID of the entry.
+    pub id: u32,
+    /// The name of the entry.
+    pub name: String,
+}
+
+/// An entry whose name changed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Renamed {
+    /// The ID of the entry.
+    pub id: u32,
+    /// The name in the old schema.
+    pub old_name: String,
+    /// The name in the new schema.
+    pub new_name: String,
+}
+
+/// Changes to one kind of entry. Entries are sorted by ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Changes {
+    /// Entries only in the new schema.
+    pub added: Vec<Entry>,
+    /// Entries only in the old schema.
+    pub removed: Vec<Entry>,
+    /// Entries in both schemas with different names.
+    pub renamed: Vec<Renamed>,
+}
+
+impl Changes {
+    fn new<T: AsRef<str>>(old: BTreeMap<u32, T>, new: BTreeMap<u32, T>) -> Self {
+        let mut changes = Self::default();
+
+        for (id, old_name) in &old {
+            let old_name = old_name.as_ref();
+
+            match new.get(id).map(AsRef::as_ref) {
+                None => changes.removed.push(Entry {
+                    id: *id,
+                    name: old_name.to_string(),
+                }),
+                Some(new_name) if new_name != old_name => changes.renamed.push(Renamed {
+                    id: *id,
+                    old_name: old_name.to_string(),
+                    new_name: new_name.to_string(),
+                }),
+                Some(_) => {},
+            }
+        }
+
+        for (id, name) in &new {
+            if !old.contains_key(id) {
+                changes.added.push(Entry {
+                    id: *id,
+                    name: name.as_ref().to_string(),
+                });
+            }
+        }
+
+        changes
+    }
+
+    /// Checks whether there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+    }
+
+    fn is_removed(&self, id: u32) -> bool {
+        self.removed.iter().any(|entry| entry.id == id)
+    }
+}
+
+/// The differences between two schemas.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{Schema, SKU};
+/// use tf2_sku::schema::SchemaDiff;
+///
+/// let old = Schema::default();
+/// let mut new = Schema::default();
+///
+/// new.particles.insert(13, "Burning Flames".into());
+///
+/// let diff = SchemaDiff::new(&old, &new);
+///
+/// assert_eq!(diff.particles.added[0].name, "Burning Flames");
+/// assert!(!diff.is_invalidated(&"378;5;u13".parse::<SKU>().unwrap()));
+/// assert!(SchemaDiff::new(&new, &old).is_invalidated(&"378;5;u13".parse::<SKU>().unwrap()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    /// Changes to items, by defindex and display name.
+    pub items: Changes,
+    /// Changes to particle effects.
+    pub particles: Changes,
+    /// Changes to paint kits.
+    pub paint_kits: Changes,
+    /// Changes to crate series, by series number and the display names of the crates with the
+    /// series. A series shared by several crates is named by all of them, sorted and separated by
+    /// commas, so it is only removed once no crate has it.
+    pub crate_series: Changes,
+}
+
+impl SchemaDiff {
+    /// Compares `old` to `new`.
+    pub fn new<A, B>(old: &A, new: &B) -> Self
+    where
+        A: ItemSchema + ?Sized,
+        B: ItemSchema + ?Sized,
+    {
+        Self {
+            items: Changes::new(item_names(old), item_names(new)),
+            particles: Changes::new(old.particles().collect(), new.particles().collect()),
+            paint_kits: Changes::new(old.paint_kits().collect(), new.paint_kits().collect()),
+            crate_series: Changes::new(crate_series(old), crate_series(new)),
+        }
+    }
+
+    /// Checks whether there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() &&
+        self.particles.is_empty() &&
+        self.paint_kits.is_empty() &&
+        self.crate_series.is_empty()
+    }
+
+    /// Checks whether a SKU refers to anything removed in the new schema.
+    pub fn is_invalidated(&self, sku: &SKU) -> bool {
+        let removed_item = |defindex: Option<u32>| defindex
+            .is_some_and(|defindex| self.items.is_removed(defindex));
+
+        removed_item(u32::try_from(sku.defindex).ok()) ||
+        removed_item(sku.target_defindex) ||
+        removed_item(sku.output_defindex) ||
+        sku.particle.is_some_and(|particle| self.particles.is_removed(particle)) ||
+        sku.skin.is_some_and(|skin| self.paint_kits.is_removed(skin)) ||
+        sku.crate_number.is_some_and(|series| self.crate_series.is_removed(series))
+    }
+
+    /// Filters SKUs that refer to anything removed in the new schema.
+    pub fn invalidated<'a, I>(&'a self, skus: I) -> impl Iterator<Item = &'a SKU> + 'a
+    where
+        I: IntoIterator<Item = &'a SKU>,
+        I::IntoIter: 'a,
+    {
+        skus.into_iter().filter(|sku| self.is_invalidated(sku))
+    }
+}
+
+fn item_names<S: ItemSchema + ?Sized>(schema: &S) -> BTreeMap<u32, &str> {
+    schema.items()
+        .map(|item| (item.defindex, item.item_name.as_ref()))
+        .collect()
+}
+
+fn crate_series<S: ItemSchema + ?Sized>(schema: &S) -> BTreeMap<u32, String> {
+    let mut names = BTreeMap::<u32, Vec<&str>>::new();
+
+    for item in schema.items() {
+        if let Some(series) = item.crate_series {
+            names.entry(series).or_default().push(item.item_name.as_ref());
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|(series, mut names)| {
+            names.sort_unstable();
+            names.dedup();
+            (series, names.join(", "))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+
+    fn schemas() -> (Schema, Schema) {
+        let old = Schema::load_items_game("tests/fixtures/items_game.txt").unwrap();
+        let mut new = old.clone();
+
+        new.items.remove(&5022);
+        new.items.get_mut(&378).unwrap().item_name = "#TF_SoldierHat1_Renamed".into();
+        new.particles.insert(3001, "#Attrib_Particle3001".into());
+        (old, new)
+    }
+
+    #[test]
+    fn identical_schemas_are_empty() {
+        let (old, _) = schemas();
+
+        assert!(SchemaDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn detects_changes() {
+        let (old, new) = schemas();
+        let diff = SchemaDiff::new(&old, &new);
+
+        assert_eq!(diff.items.removed, vec![Entry {
+            id: 5022,
+            name: "#TF_SupplyCrate".into(),
+        }]);
+        assert_eq!(diff.items.renamed[0].new_name, "#TF_SoldierHat1_Renamed");
+        assert_eq!(diff.particles.added[0].id, 3001);
+        assert_eq!(diff.crate_series.removed[0].id, 1);
+        assert!(diff.paint_kits.is_empty());
+    }
+
+    #[test]
+    fn compares_crates_sharing_a_series() {
+        let (mut old, _) = schemas();
+        let mut reissue = old.items[&5022].clone();
+
+        reissue.defindex = 5023;
+        reissue.item_name = "#TF_SupplyCrate_Reissue".into();
+        old.items.insert(5023, reissue);
+
+        let mut new = old.clone();
+
+        new.items.remove(&5022);
+
+        let diff = SchemaDiff::new(&old, &new);
+
+        assert!(diff.crate_series.removed.is_empty());
+        assert_eq!(diff.crate_series.renamed, vec![Renamed {
+            id: 1,
+            old_name: "#TF_SupplyCrate, #TF_SupplyCrate_Reissue".into(),
+            new_name: "#TF_SupplyCrate_Reissue".into(),
+        }]);
+        assert!(!diff.is_invalidated(&"5023;6;c1".parse::<SKU>().unwrap()));
+    }
+
+    #[test]
+    fn flags_invalidated_skus() {
+        let (old, new) = schemas();
+        let diff = SchemaDiff::new(&old, &new);
+        let skus = ["5022;6;c1", "205;11", "6526;6;kt-3;td-5022"]
+            .map(|s| s.parse::<SKU>().unwrap());
+        let invalidated = diff.invalidated(&skus).map(|sku| sku.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(invalidated, vec!["5022;6;c1", "6526;6;kt-3;td-5022"]);
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let (old, new) = schemas();
+        let json = serde_json::to_value(SchemaDiff::new(&old, &new)).unwrap();
+
+        assert_eq!(json["particles"]["added"][0]["id"], 3001);
+        assert_eq!(json["items"]["renamed"][0]["old_name"], "#TF_SoldierHat1");
+    }
+}
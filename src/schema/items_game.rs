@@ -0,0 +1,344 @@
+//! Building a [`Schema`] from `items_game.txt`.
+
+use super::{Schema, SchemaAttribute, SchemaItem};
+use crate::error::SchemaError;
+use crate::keyvalues::{KeyValues, Value};
+use std::borrow::Cow;
+use std::path::Path;
+use std::str::FromStr;
+use tf2_enum::{Capability, CraftClass, ItemSlot, Quality};
+
+const KEY_ITEMS_GAME: &str = "items_game";
+const KEY_ITEMS: &str = "items";
+const KEY_PREFABS: &str = "prefabs";
+const KEY_QUALITIES: &str = "qualities";
+const KEY_PARTICLES: &str = "attribute_controlled_attached_particles";
+const KEY_ATTRIBUTES: &str = "attributes";
+const KEY_LOOT_LISTS: &str = "client_loot_lists";
+const KEY_CRATE_LOOT_LISTS: &str = "revolving_loot_lists";
+const ATTRIBUTE_CRATE_SERIES: &str = "set supply crate series";
+const ATTRIBUTE_PAINT_KIT: &str = "paintkit_proto_def_index";
+
+impl Schema {
+    /// Builds a schema from a parsed `items_game.txt`. Prefabs and the "default" item are
+    /// resolved into each item.
+    ///
+    /// Names are taken as they appear in the file, so display names for items, particles and
+    /// paint kits are localization tokens (e.g. "#TF_Weapon_RocketLauncher").
+    ///
+    /// Attribute definitions and the loot lists of crates are read too. Loot list entries with
+    /// criteria rather than a name, such as "item_criteria" blocks, are skipped.
+    ///
+    /// # Errors
+    /// - The document has no "items" block.
+    /// - An item refers to a prefab that does not exist, or prefabs form a cycle.
+    pub fn from_items_game(kv: &KeyValues) -> Result<Self, SchemaError> {
+        // The document is usually wrapped in an "items_game" block.
+        let root = kv.get_object(KEY_ITEMS_GAME).unwrap_or(kv);
+        let items = root.get_object(KEY_ITEMS)
+            .ok_or(SchemaError::MissingBlock(KEY_ITEMS))?;
+        let empty = KeyValues::new();
+        let prefabs = root.get_object(KEY_PREFABS).unwrap_or(&empty);
+        let qualities = root.get_object(KEY_QUALITIES).unwrap_or(&empty);
+        let defaults = items.get_object("default").unwrap_or(&empty);
+        let mut schema = Schema::default();
+
+        for (key, value) in items.iter() {
+            let (Ok(defindex), Some(item)) = (key.parse::<u32>(), value.as_object()) else {
+                continue;
+            };
+            let mut resolved = defaults.clone();
+
+            resolved.merge(&item.resolve_prefabs(prefabs)?);
+
+            let item = schema_item(defindex, &resolved, qualities);
+
+            if let Some(paint_kit) = item.paint_kit {
+                schema.paint_kits
+                    .entry(paint_kit)
+                    // Paint kit definitions are type 9 in `tf_proto_obj_defs_<language>.txt`.
+                    .or_insert_with(|| format!("#9_{paint_kit}_field"));
+            }
+
+            schema.items.insert(defindex, item);
+        }
+
+        if let Some(particles) = root.get_object(KEY_PARTICLES) {
+            collect_particles(particles, &mut schema);
+        }
+
+        if let Some(attributes) = root.get_object(KEY_ATTRIBUTES) {
+            collect_attributes(attributes, &mut schema);
+        }
+
+        if let Some(loot_lists) = root.get_object(KEY_LOOT_LISTS) {
+            for (name, value) in loot_lists.iter() {
+                let Some(loot_list) = value.as_object() else {
+                    continue;
+                };
+                // Entries name an item or another loot list, and blocks hold criteria.
+                let entries = loot_list
+                    .iter()
+                    .filter(|(_, value)| value.as_str().is_some())
+                    .map(|(entry, _)| entry.to_string())
+                    .collect();
+
+                schema.loot_lists.insert(name.to_string(), entries);
+            }
+        }
+
+        if let Some(crate_loot_lists) = root.get_object(KEY_CRATE_LOOT_LISTS) {
+            for (key, value) in crate_loot_lists.iter() {
+                if let (Ok(crate_series), Some(name)) = (key.parse::<u32>(), value.as_str()) {
+                    schema.crate_loot_lists.insert(crate_series, name.to_string());
+                }
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Loads a schema from an `items_game.txt` file. See [`Schema::from_items_game`].
+    pub fn load_items_game<P: AsRef<Path>>(path: P) -> Result<Self, SchemaError> {
+        Self::from_items_game(&KeyValues::load(path)?)
+    }
+}
+
+fn schema_item(
+    defindex: u32,
+    kv: &KeyValues,
+    qualities: &KeyValues,
+) -> SchemaItem {
+    let name = kv.get_str("name")
+        .map(str::to_string)
+        .unwrap_or_else(|| defindex.to_string());
+    let item_name = kv.get_str("item_name")
+        .map(str::to_string)
+        .unwrap_or_else(|| name.clone());
+    let capabilities = kv.get_object("capabilities")
+        .map(|capabilities| capabilities
+            .iter()
+            .filter(|(_, value)| value.as_str() == Some("1"))
+            .filter_map(|(key, _)| Capability::from_str(key).ok())
+            .collect::<Vec<_>>())
+        .unwrap_or_default();
+    let static_attrs = kv.get_object("static_attrs");
+
+    SchemaItem {
+        defindex,
+        name: Cow::Owned(name),
+        item_name: Cow::Owned(item_name),
+        proper_name: kv.get_str("proper_name") == Some("1"),
+        item_class: Cow::Owned(kv.get_str("item_class").unwrap_or_default().to_string()),
+        item_quality: kv.get_str("item_quality")
+            .and_then(|quality| parse_quality(quality, qualities))
+            .unwrap_or(Quality::Normal),
+        craft_class: kv.get_str("craft_class")
+            .and_then(|craft_class| CraftClass::from_str(craft_class).ok()),
+        item_slot: kv.get_str("item_slot")
+            .and_then(|item_slot| ItemSlot::from_str(item_slot).ok()),
+        tool_type: kv.get_object("tool")
+            .and_then(|tool| tool.get_str("type"))
+            .map(|tool_type| Cow::Owned(tool_type.to_string())),
+        crate_series: static_attrs
+            .and_then(|attrs| static_attr_u32(attrs, ATTRIBUTE_CRATE_SERIES)),
+        paint_kit: static_attrs
+            .and_then(|attrs| static_attr_u32(attrs, ATTRIBUTE_PAINT_KIT)),
+        capabilities: Cow::Owned(capabilities),
+    }
+}
+
+/// Gets a quality by its name in the "qualities" block, e.g. "unique".
+fn parse_quality(name: &str, qualities: &KeyValues) -> Option<Quality> {
+    let value = qualities
+        .get_object(name)
+        .and_then(|quality| quality.get_str("value"))
+        .and_then(|value| value.parse::<u32>().ok());
+
+    if let Some(value) = value {
+        return Quality::try_from(value).ok();
+    }
+
+    // Fall back to the well-known names when the block is absent.
+    Some(match name.to_ascii_lowercase().as_str() {
+        "normal" => Quality::Normal,
+        "rarity1" | "genuine" => Quality::Genuine,
+        "rarity2" => Quality::Rarity2,
+        "vintage" => Quality::Vintage,
+        "rarity3" => Quality::Rarity3,
+        "rarity4" | "unusual" => Quality::Unusual,
+        "unique" => Quality::Unique,
+        "community" => Quality::Community,
+        "developer" => Quality::Valve,
+        "selfmade" => Quality::SelfMade,
+        "customized" => Quality::Customized,
+        "strange" => Quality::Strange,
+        "completed" => Quality::Completed,
+        "haunted" => Quality::Haunted,
+        "collectors" => Quality::Collectors,
+        "paintkitweapon" => Quality::DecoratedWeapon,
+        _ => return None,
+    })
+}
+
+/// Static attribute values are either a plain string or a block with a "value" key.
+fn static_attr_u32(attrs: &KeyValues, name: &str) -> Option<u32> {
+    let value = match attrs.get(name)? {
+        Value::String(value) => value.as_str(),
+        Value::Object(attr) => attr.get_str("value")?,
+    };
+
+    value.parse::<f32>()
+        .ok()
+        .filter(|value| value.fract() == 0.0 && *value >= 0.0)
+        .map(|value| value as u32)
+}
+
+/// Particles are nested in groups such as "cosmetic_unusual_effects". Each is a block keyed by
+/// its ID with a "system" key.
+fn collect_particles(kv: &KeyValues, schema: &mut Schema) {
+    for (key, value) in kv.iter() {
+        let Some(block) = value.as_object() else {
+            continue;
+        };
+
+        match key.parse::<u32>() {
+            Ok(id) if block.get("system").is_some() => {
+                schema.particles.insert(id, format!("#Attrib_Particle{id}"));
+            },
+            _ => collect_particles(block, schema),
+        }
+    }
+}
+
+/// Attributes are blocks keyed by their defindex.
+fn collect_attributes(kv: &KeyValues, schema: &mut Schema) {
+    for (key, value) in kv.iter() {
+        let (Ok(defindex), Some(attribute)) = (key.parse::<u32>(), value.as_object()) else {
+            continue;
+        };
+        let Some(name) = attribute.get_str("name") else {
+            continue;
+        };
+
+        schema.attributes.insert(defindex, SchemaAttribute {
+            defindex,
+            name: name.to_string(),
+            attribute_class: attribute.get_str("attribute_class").unwrap_or_default().to_string(),
+            stored_as_integer: attribute.get_str("stored_as_integer") == Some("1"),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ItemSchema;
+    use crate::SKU;
+    use crate::error::ValidationError;
+    use tf2_enum::{KillstreakTier, Paint};
+
+    fn schema() -> Schema {
+        Schema::load_items_game("tests/fixtures/items_game.txt").unwrap()
+    }
+
+    #[test]
+    fn resolves_prefabs() {
+        let schema = schema();
+        let item = schema.item(205).unwrap();
+
+        assert_eq!(item.item_name, "#TF_Weapon_RocketLauncher");
+        assert_eq!(item.item_class, "tf_weapon_rocketlauncher");
+        assert_eq!(item.item_quality, Quality::Unique);
+        assert_eq!(item.craft_class, Some(CraftClass::Weapon));
+        assert!(item.has_capability(Capability::CanKillstreakify));
+        assert!(!item.proper_name);
+    }
+
+    #[test]
+    fn own_values_override_prefabs() {
+        let schema = schema();
+
+        assert_eq!(schema.item(18).unwrap().item_quality, Quality::Normal);
+    }
+
+    #[test]
+    fn merges_base_file() {
+        let schema = schema();
+        let item = schema.item(378).unwrap();
+
+        assert!(item.proper_name);
+        assert!(item.has_capability(Capability::Paintable));
+    }
+
+    #[test]
+    fn reads_static_attributes() {
+        let schema = schema();
+
+        assert_eq!(schema.item(5022).unwrap().crate_series, Some(1));
+        assert_eq!(schema.item(16310).unwrap().paint_kit, Some(310));
+        assert_eq!(schema.item(6526).unwrap().tool_type.as_deref(), Some("killstreakifier"));
+    }
+
+    #[test]
+    fn names_paint_kits_by_token() {
+        assert_eq!(schema().paint_kit_name(310), Some("#9_310_field"));
+    }
+
+    #[test]
+    fn collects_particles() {
+        let schema = schema();
+
+        assert_eq!(schema.particle_name(13), Some("#Attrib_Particle13"));
+        assert_eq!(schema.particle_name(703), Some("#Attrib_Particle703"));
+    }
+
+    #[test]
+    fn reads_attributes() {
+        let schema = schema();
+        let attribute = &schema.attributes[&187];
+
+        assert_eq!(attribute.name, ATTRIBUTE_CRATE_SERIES);
+        assert_eq!(attribute.attribute_class, "supply_crate_series");
+        assert!(!attribute.stored_as_integer);
+        assert!(schema.attributes[&834].stored_as_integer);
+    }
+
+    #[test]
+    fn reads_loot_lists() {
+        let schema = schema();
+        let defindexes = |items: Vec<&SchemaItem>| items
+            .into_iter()
+            .map(|item| item.defindex)
+            .collect::<Vec<_>>();
+
+        assert_eq!(schema.crate_loot_lists[&1], "mannco_crate_1");
+        assert_eq!(schema.loot_lists["mannco_crate_1"], ["Upgradeable TF_WEAPON_ROCKETLAUNCHER", "crate_1_tools"]);
+        assert_eq!(defindexes(schema.crate_items(1)), [205, 6526]);
+        assert!(schema.crate_items(2).is_empty());
+    }
+
+    #[test]
+    fn validates_sku() {
+        let schema = schema();
+        let mut sku = "205;11;kt-3".parse::<SKU>().unwrap();
+
+        assert_eq!(sku.validate(&schema), Ok(()));
+
+        sku.paint = Some(Paint::PinkAsHell);
+        assert_eq!(sku.validate(&schema), Err(ValidationError::NotPaintable));
+        assert_eq!("5022;6;c2".parse::<SKU>().unwrap().validate(&schema), Err(ValidationError::CrateSeriesMismatch {
+            expected: 1,
+            found: 2,
+        }));
+        assert_eq!("378;5;u9999".parse::<SKU>().unwrap().validate(&schema), Err(ValidationError::UnknownParticle(9999)));
+
+        let kit = SKU {
+            killstreak_tier: Some(KillstreakTier::Professional),
+            target_defindex: Some(205),
+            ..SKU::new(6526, Quality::Unique)
+        };
+
+        assert_eq!(kit.validate(&schema), Ok(()));
+    }
+}
@@ -0,0 +1,282 @@
+//! Item schema data used to name and validate SKUs.
+//!
+//! The [`ItemSchema`] trait is the interface everything schema-dependent in this crate is written
+//! against. [`Schema`] is an owned snapshot implementing it, which can be built from
+//! `items_game.txt` or (de)serialized as JSON.
+
+mod diff;
+mod items_game;
+mod registry;
+
+pub use diff::{Changes, Entry, Renamed, SchemaDiff};
+pub use registry::{SchemaRegistry, Watcher};
+
+use crate::SKU;
+use crate::error::ValidationError;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tf2_enum::{Capability, CraftClass, ItemSlot, Quality};
+
+/// An item definition.
+///
+/// String fields are [`Cow`] so definitions can also be stored in static tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaItem {
+    /// The defindex of the item.
+    pub defindex: u32,
+    /// The internal name of the item e.g. "TF_WEAPON_ROCKETLAUNCHER".
+    pub name: Cow<'static, str>,
+    /// The display name of the item e.g. "Rocket Launcher". Items loaded from `items_game.txt`
+    /// have a localization token here (e.g. "#TF_Weapon_RocketLauncher") until localized.
+    pub item_name: Cow<'static, str>,
+    /// Whether the name is prefixed with "The" when it has no other prefixes.
+    #[serde(default)]
+    pub proper_name: bool,
+    /// The item class e.g. "tf_weapon_rocketlauncher" or "tool".
+    #[serde(default)]
+    pub item_class: Cow<'static, str>,
+    /// The default quality of the item.
+    pub item_quality: Quality,
+    /// The craft class of the item.
+    #[serde(default)]
+    pub craft_class: Option<CraftClass>,
+    /// The item slot of the item.
+    #[serde(default)]
+    pub item_slot: Option<ItemSlot>,
+    /// The tool type for tools e.g. "killstreakifier" or "strangifier".
+    #[serde(default)]
+    pub tool_type: Option<Cow<'static, str>>,
+    /// The crate series of the item, for crates with a fixed series.
+    #[serde(default)]
+    pub crate_series: Option<u32>,
+    /// The paint kit applied to the item, for pre-painted war paint items.
+    #[serde(default)]
+    pub paint_kit: Option<u32>,
+    /// The capabilities of the item.
+    #[serde(default)]
+    pub capabilities: Cow<'static, [Capability]>,
+}
+
+impl SchemaItem {
+    /// Checks whether the item has a capability.
+    pub fn has_capability(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+/// Provides item schema data.
+pub trait ItemSchema {
+    /// Gets an item by its defindex.
+    fn item(&self, defindex: u32) -> Option<&SchemaItem>;
+
+    /// Iterates over all items.
+    fn items(&self) -> Box<dyn Iterator<Item = &SchemaItem> + '_>;
+
+    /// Gets the name of a particle effect.
+    fn particle_name(&self, particle: u32) -> Option<&str>;
+
+    /// Iterates over all particle effects and their names.
+    fn particles(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_>;
+
+    /// Gets the name of a paint kit (skin).
+    fn paint_kit_name(&self, paint_kit: u32) -> Option<&str>;
+
+    /// Iterates over all paint kits and their names.
+    fn paint_kits(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_>;
+
+    /// Gets the item for a SKU's defindex. Negative defindexes are never in the schema.
+    fn sku_item(&self, sku: &SKU) -> Option<&SchemaItem> {
+        u32::try_from(sku.defindex)
+            .ok()
+            .and_then(|defindex| self.item(defindex))
+    }
+}
+
+macro_rules! impl_item_schema_for_pointer {
+    ($($t:ty),*) => {
+        $(
+            impl<T: ItemSchema + ?Sized> ItemSchema for $t {
+                fn item(&self, defindex: u32) -> Option<&SchemaItem> {
+                    (**self).item(defindex)
+                }
+
+                fn items(&self) -> Box<dyn Iterator<Item = &SchemaItem> + '_> {
+                    (**self).items()
+                }
+
+                fn particle_name(&self, particle: u32) -> Option<&str> {
+                    (**self).particle_name(particle)
+                }
+
+                fn particles(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+                    (**self).particles()
+                }
+
+                fn paint_kit_name(&self, paint_kit: u32) -> Option<&str> {
+                    (**self).paint_kit_name(paint_kit)
+                }
+
+                fn paint_kits(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+                    (**self).paint_kits()
+                }
+            }
+        )*
+    };
+}
+
+impl_item_schema_for_pointer!(&T, Box<T>, Arc<T>);
+
+/// An attribute definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaAttribute {
+    /// The defindex of the attribute.
+    pub defindex: u32,
+    /// The name of the attribute e.g. "set supply crate series".
+    pub name: String,
+    /// The attribute class, which the game reads the attribute by e.g. "supply_crate_series".
+    #[serde(default)]
+    pub attribute_class: String,
+    /// Whether the value is stored as an integer rather than a float.
+    #[serde(default)]
+    pub stored_as_integer: bool,
+}
+
+/// An owned schema snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schema {
+    /// Items by defindex.
+    pub items: BTreeMap<u32, SchemaItem>,
+    /// Particle effect names by particle ID.
+    #[serde(default)]
+    pub particles: BTreeMap<u32, String>,
+    /// Paint kit names by paint kit ID.
+    #[serde(default)]
+    pub paint_kits: BTreeMap<u32, String>,
+    /// Attribute definitions by defindex.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<u32, SchemaAttribute>,
+    /// Loot lists by name. Each entry is the internal name of an item or of another loot list.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub loot_lists: BTreeMap<String, Vec<String>>,
+    /// The names of the loot lists of crates, by crate series.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub crate_loot_lists: BTreeMap<u32, String>,
+}
+
+impl Schema {
+    /// Gets the items a loot list can give, including those of the loot lists it contains. Items
+    /// are named by their internal name, and entries naming neither an item nor a loot list are
+    /// skipped.
+    pub fn loot_list_items(&self, name: &str) -> Vec<&SchemaItem> {
+        let mut items = Vec::new();
+        let mut visited = vec![name];
+        let mut pending = vec![name];
+
+        while let Some(name) = pending.pop() {
+            for entry in self.loot_lists.get(name).into_iter().flatten() {
+                if let Some(item) = self.items.values().find(|item| item.name == *entry) {
+                    items.push(item);
+                } else if self.loot_lists.contains_key(entry) && !visited.contains(&entry.as_str()) {
+                    visited.push(entry);
+                    pending.push(entry);
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Gets the items a crate of a series can give. See [`Schema::loot_list_items`].
+    pub fn crate_items(&self, crate_series: u32) -> Vec<&SchemaItem> {
+        self.crate_loot_lists
+            .get(&crate_series)
+            .map(|name| self.loot_list_items(name))
+            .unwrap_or_default()
+    }
+}
+
+impl ItemSchema for Schema {
+    fn item(&self, defindex: u32) -> Option<&SchemaItem> {
+        self.items.get(&defindex)
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &SchemaItem> + '_> {
+        Box::new(self.items.values())
+    }
+
+    fn particle_name(&self, particle: u32) -> Option<&str> {
+        self.particles.get(&particle).map(String::as_str)
+    }
+
+    fn particles(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+        Box::new(self.particles.iter().map(|(id, name)| (*id, name.as_str())))
+    }
+
+    fn paint_kit_name(&self, paint_kit: u32) -> Option<&str> {
+        self.paint_kits.get(&paint_kit).map(String::as_str)
+    }
+
+    fn paint_kits(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+        Box::new(self.paint_kits.iter().map(|(id, name)| (*id, name.as_str())))
+    }
+}
+
+impl SKU {
+    /// Checks this SKU against a schema. This verifies that every item, particle and paint kit it
+    /// references exists, and that its attributes are applicable to the item.
+    ///
+    /// # Errors
+    /// Returns the first problem found.
+    pub fn validate<S: ItemSchema + ?Sized>(&self, schema: &S) -> Result<(), ValidationError> {
+        let item = schema.sku_item(self)
+            .ok_or(ValidationError::UnknownItem(self.defindex))?;
+
+        if let Some(particle) = self.particle {
+            if schema.particle_name(particle).is_none() {
+                return Err(ValidationError::UnknownParticle(particle));
+            }
+        }
+
+        if let Some(skin) = self.skin {
+            if schema.paint_kit_name(skin).is_none() {
+                return Err(ValidationError::UnknownPaintKit(skin));
+            }
+        }
+
+        if let Some(target_defindex) = self.target_defindex {
+            if schema.item(target_defindex).is_none() {
+                return Err(ValidationError::UnknownTarget(target_defindex));
+            }
+        }
+
+        if let Some(output_defindex) = self.output_defindex {
+            if schema.item(output_defindex).is_none() {
+                return Err(ValidationError::UnknownOutput(output_defindex));
+            }
+        }
+
+        if self.paint.is_some() && !item.has_capability(Capability::Paintable) {
+            return Err(ValidationError::NotPaintable);
+        }
+
+        // Kits and fabricators carry a killstreak tier without being killstreakable themselves.
+        if self.killstreak_tier.is_some() &&
+            item.tool_type.is_none() &&
+            !item.has_capability(Capability::CanKillstreakify) {
+            return Err(ValidationError::NotKillstreakable);
+        }
+
+        if let (Some(expected), Some(found)) = (item.crate_series, self.crate_number) {
+            if expected != found {
+                return Err(ValidationError::CrateSeriesMismatch {
+                    expected,
+                    found,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! A shared schema that can be replaced while in use.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// Holds the current schema snapshot, shared between threads.
+///
+/// Readers take a snapshot with [`SchemaRegistry::load`], which is an [`Arc`] clone, and keep a
+/// consistent view for as long as they hold it. Replacing the schema swaps the pointer and never
+/// waits on readers that are using an older snapshot.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{ItemSchema, Schema};
+/// use tf2_sku::schema::SchemaRegistry;
+///
+/// let registry = SchemaRegistry::new(Schema::default());
+/// let snapshot = registry.load();
+/// let mut schema = Schema::default();
+///
+/// schema.particles.insert(13, "Burning Flames".into());
+/// registry.store(schema);
+///
+/// // The old snapshot is unchanged.
+/// assert!(snapshot.particle_name(13).is_none());
+/// assert_eq!(registry.load().particle_name(13), Some("Burning Flames"));
+/// ```
+#[derive(Debug, Default)]
+pub struct SchemaRegistry<S> {
+    current: RwLock<Arc<S>>,
+}
+
+impl<S> SchemaRegistry<S> {
+    /// Creates a registry holding `schema`.
+    pub fn new(schema: S) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(schema)),
+        }
+    }
+
+    /// Gets the current snapshot.
+    pub fn load(&self) -> Arc<S> {
+        // A panic while holding the lock cannot leave the pointer in an invalid state.
+        let current = self.current.read().unwrap_or_else(|error| error.into_inner());
+
+        Arc::clone(&current)
+    }
+
+    /// Replaces the current snapshot, returning the previous one.
+    pub fn store(&self, schema: S) -> Arc<S> {
+        self.swap(Arc::new(schema))
+    }
+
+    /// Replaces the current snapshot with an existing [`Arc`], returning the previous one.
+    pub fn swap(&self, schema: Arc<S>) -> Arc<S> {
+        let mut current = self.current.write().unwrap_or_else(|error| error.into_inner());
+
+        std::mem::replace(&mut *current, schema)
+    }
+}
+
+impl<S> SchemaRegistry<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Watches a file, reloading the schema with `load` whenever its modification time or size
+    /// changes. The file is checked every `interval`. The schema is not loaded until the file
+    /// first changes, so the registry should be created with its current contents.
+    ///
+    /// A failed reload keeps the current schema. The most recent error can be taken from the
+    /// returned [`Watcher`]. Watching stops when the [`Watcher`] is dropped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use tf2_sku::Schema;
+    /// use tf2_sku::schema::SchemaRegistry;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let path = "items_game.txt";
+    /// let registry = Arc::new(SchemaRegistry::new(Schema::load_items_game(path).unwrap()));
+    /// let watcher = SchemaRegistry::watch(
+    ///     &registry,
+    ///     path,
+    ///     Duration::from_secs(5),
+    ///     |path| Schema::load_items_game(path),
+    /// );
+    /// ```
+    pub fn watch<P, F, E>(
+        registry: &Arc<Self>,
+        path: P,
+        interval: Duration,
+        mut load: F,
+    ) -> Watcher<E>
+    where
+        P: Into<PathBuf>,
+        F: FnMut(&Path) -> Result<S, E> + Send + 'static,
+        E: Send + 'static,
+    {
+        let path = path.into();
+        let registry = Arc::clone(registry);
+        let error = Arc::new(Mutex::new(None));
+        let thread_error = Arc::clone(&error);
+        let (stop, stopped) = mpsc::channel::<()>();
+        let mut last_version = file_version(&path);
+        let handle = thread::spawn(move || loop {
+            match stopped.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => {},
+                // Stopped or the watcher was dropped.
+                _ => return,
+            }
+
+            let version = file_version(&path);
+
+            // The file may be missing while it is being replaced.
+            if version.is_none() || version == last_version {
+                continue;
+            }
+
+            last_version = version;
+
+            match load(&path) {
+                Ok(schema) => {
+                    registry.store(schema);
+                },
+                Err(e) => {
+                    *thread_error.lock().unwrap_or_else(|error| error.into_inner()) = Some(e);
+                },
+            }
+        });
+
+        Watcher {
+            stop: Some(stop),
+            handle: Some(handle),
+            error,
+        }
+    }
+}
+
+/// Handle for a file being watched by [`SchemaRegistry::watch`]. Watching stops when this is
+/// dropped.
+#[derive(Debug)]
+pub struct Watcher<E> {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+    error: Arc<Mutex<Option<E>>>,
+}
+
+impl<E> Watcher<E> {
+    /// Takes the error from the most recent failed reload, if any.
+    pub fn take_error(&self) -> Option<E> {
+        self.error.lock().unwrap_or_else(|error| error.into_inner()).take()
+    }
+}
+
+impl<E> Drop for Watcher<E> {
+    fn drop(&mut self) {
+        // Dropping the sender wakes the thread.
+        self.stop.take();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn file_version(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn wait_for<F: Fn() -> bool>(condition: F) -> bool {
+        let started = Instant::now();
+
+        while started.elapsed() < Duration::from_secs(5) {
+            if condition() {
+                return true;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        false
+    }
+
+    #[test]
+    fn readers_keep_their_snapshot() {
+        let registry = SchemaRegistry::new(1);
+        let snapshot = registry.load();
+        let previous = registry.store(2);
+
+        assert_eq!(*snapshot, 1);
+        assert_eq!(*previous, 1);
+        assert_eq!(*registry.load(), 2);
+    }
+
+    #[test]
+    fn reloads_on_change() {
+        let path = std::env::temp_dir()
+            .join(format!("tf2-sku-registry-{}.txt", std::process::id()));
+
+        fs::write(&path, "1").unwrap();
+
+        let registry = Arc::new(SchemaRegistry::new(1u32));
+        let watcher = SchemaRegistry::watch(
+            &registry,
+            &path,
+            Duration::from_millis(10),
+            |path| fs::read_to_string(path).unwrap().trim().parse::<u32>(),
+        );
+
+        fs::write(&path, "22").unwrap();
+        assert!(wait_for(|| *registry.load() == 22));
+
+        // A failed reload keeps the current value.
+        fs::write(&path, "not a number").unwrap();
+        assert!(wait_for(|| watcher.take_error().is_some()));
+        assert_eq!(*registry.load(), 22);
+
+        drop(watcher);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,557 @@
+//! Fuzzy item search.
+//!
+//! Queries are matched word by word against item names, with the words left over read as
+//! qualities, effects, killstreak tiers, wears, war paints and other attributes. Words match when
+//! they are equal, a prefix (e.g. "launch" for "launcher") or a close typo. Multi-word names can
+//! also be written by their initials, e.g. "bf" for "Burning Flames" or "ft" for "Field-Tested",
+//! and common trader abbreviations such as "pro ks" and "nc" are understood.
+
+use crate::SKU;
+use crate::locale::{Localization, Word};
+use crate::name::{English, Vocabulary, narrow};
+use crate::schema::{ItemSchema, SchemaItem};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use tf2_enum::{
+    CraftClass,
+    IntoEnumIterator,
+    KillstreakTier,
+    Killstreaker,
+    Quality,
+    Sheen,
+    Wear,
+};
+
+/// The lowest score included in results.
+const MIN_SCORE: f32 = 0.4;
+
+/// Abbreviations not covered by prefixes or initials.
+const ABBREVIATIONS: &[(&str, Attribute)] = &[
+    ("nc", Attribute::NonCraftable),
+    ("non craftable", Attribute::NonCraftable),
+    ("uncraftable", Attribute::NonCraftable),
+    ("ks", Attribute::KillstreakTier(KillstreakTier::Killstreak)),
+    ("spec ks", Attribute::KillstreakTier(KillstreakTier::Specialized)),
+    ("sks", Attribute::KillstreakTier(KillstreakTier::Specialized)),
+    ("pro ks", Attribute::KillstreakTier(KillstreakTier::Professional)),
+    ("pks", Attribute::KillstreakTier(KillstreakTier::Professional)),
+    ("festive", Attribute::Festivized),
+    ("festivized", Attribute::Festivized),
+    ("aus", Attribute::Australium),
+    ("australium", Attribute::Australium),
+    ("unu", Attribute::Quality(Quality::Unusual)),
+];
+
+/// A search result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchMatch {
+    /// The matched SKU.
+    pub sku: SKU,
+    /// How well the query matches, from 0 to 1.
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Attribute {
+    NonCraftable,
+    Quality(Quality),
+    Particle(u32),
+    KillstreakTier(KillstreakTier),
+    Sheen(Sheen),
+    Killstreaker(Killstreaker),
+    Wear(Wear),
+    Skin(u32),
+    Festivized,
+    Australium,
+}
+
+/// A phrase naming an attribute.
+#[derive(Debug, Clone)]
+struct Term {
+    words: Vec<String>,
+    attribute: Attribute,
+}
+
+/// An index for searching the items in a schema. Building the index takes a pass over the
+/// schema, so it should be reused between searches.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{Schema, SchemaItem};
+/// use tf2_sku::search::ItemSearch;
+/// use tf2_enum::Quality;
+///
+/// let mut schema = Schema::default();
+///
+/// schema.items.insert(205, SchemaItem {
+///     defindex: 205,
+///     name: "Upgradeable TF_WEAPON_ROCKETLAUNCHER".into(),
+///     item_name: "Rocket Launcher".into(),
+///     proper_name: false,
+///     item_class: "tf_weapon_rocketlauncher".into(),
+///     item_quality: Quality::Unique,
+///     craft_class: None,
+///     item_slot: None,
+///     tool_type: None,
+///     crate_series: None,
+///     paint_kit: None,
+///     capabilities: Default::default(),
+/// });
+///
+/// let search = ItemSearch::new(&schema);
+/// let matches = search.search("pro ks rocket launch", 5);
+///
+/// assert_eq!(matches[0].sku.to_string(), "205;6;kt-3");
+/// ```
+#[derive(Debug)]
+pub struct ItemSearch<'a, S: ?Sized> {
+    schema: &'a S,
+    /// Items grouped by name, with the normalized words of the name.
+    items: Vec<(Vec<String>, Vec<&'a SchemaItem>)>,
+    terms: Vec<Term>,
+}
+
+impl<'a, S> ItemSearch<'a, S>
+where
+    S: ItemSchema + ?Sized,
+{
+    /// Builds a search index over a schema.
+    ///
+    /// Names are indexed as the schema gives them. A schema built by
+    /// [`Schema::from_items_game`](crate::Schema::from_items_game) names its items by
+    /// localization tokens, so localize it first with
+    /// [`Localization::localize`](crate::locale::Localization::localize) or use
+    /// [`ItemSearch::with_localization`].
+    pub fn new(schema: &'a S) -> Self {
+        Self::build(schema, &English)
+    }
+
+    /// Builds a search index over a schema, with item, effect and paint kit names that are
+    /// localization tokens resolved from `locale`. Qualities, killstreaks and wears are matched
+    /// in the language of `locale` as well as in English.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use tf2_sku::Schema;
+    /// use tf2_sku::locale::Localization;
+    /// use tf2_sku::search::ItemSearch;
+    ///
+    /// let schema = Schema::load_items_game("items_game.txt").unwrap();
+    /// let locale = Localization::load("tf_english.txt").unwrap();
+    /// let search = ItemSearch::with_localization(&schema, &locale);
+    /// let matches = search.search("strange rocket launcher", 5);
+    /// ```
+    pub fn with_localization(schema: &'a S, locale: &Localization) -> Self {
+        Self::build(schema, locale)
+    }
+
+    fn build<V: Vocabulary>(schema: &'a S, vocabulary: &V) -> Self {
+        let mut names: HashMap<Vec<String>, Vec<&SchemaItem>> = HashMap::new();
+
+        for item in schema.items() {
+            names.entry(words(vocabulary.resolve(&item.item_name))).or_default().push(item);
+        }
+
+        let mut items = names.into_iter().collect::<Vec<_>>();
+        // Keeps results with equal scores in a stable order.
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut terms = Vec::new();
+        let mut add_term = |name: &str, attribute: Attribute| {
+            let words = words(name);
+
+            if words.len() > 1 {
+                terms.push(Term {
+                    words: vec![words.iter().filter_map(|word| word.chars().next()).collect()],
+                    attribute,
+                });
+            }
+
+            terms.push(Term {
+                words,
+                attribute,
+            });
+        };
+
+        for (name, attribute) in ABBREVIATIONS {
+            add_term(name, *attribute);
+        }
+
+        // Attribute words are matched in English, and in the vocabulary's language too.
+        let mut add_word = |word: Word, attribute: Attribute| {
+            let english = word.english();
+            let localized = vocabulary.word(word);
+
+            add_term(&english, attribute);
+
+            if localized != english {
+                add_term(&localized, attribute);
+            }
+        };
+
+        for quality in Quality::iter() {
+            add_word(Word::Quality(quality), Attribute::Quality(quality));
+        }
+
+        for killstreak_tier in KillstreakTier::iter() {
+            add_word(Word::KillstreakTier(killstreak_tier), Attribute::KillstreakTier(killstreak_tier));
+        }
+
+        for sheen in Sheen::iter() {
+            add_word(Word::Sheen(sheen), Attribute::Sheen(sheen));
+        }
+
+        for killstreaker in Killstreaker::iter() {
+            add_word(Word::Killstreaker(killstreaker), Attribute::Killstreaker(killstreaker));
+        }
+
+        for wear in Wear::iter() {
+            add_word(Word::Wear(wear), Attribute::Wear(wear));
+        }
+
+        for (particle, name) in schema.particles() {
+            add_term(vocabulary.resolve(name), Attribute::Particle(particle));
+        }
+
+        for (paint_kit, name) in schema.paint_kits() {
+            add_term(vocabulary.resolve(name), Attribute::Skin(paint_kit));
+        }
+
+        Self {
+            schema,
+            items,
+            terms,
+        }
+    }
+
+    /// Searches for items matching a query, returning up to `limit` matches ordered from best
+    /// to worst.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchMatch> {
+        let query = words(query)
+            .into_iter()
+            .filter(|word| word != "the")
+            .collect::<Vec<_>>();
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches = self.items
+            .iter()
+            .flat_map(|(name, items)| self.score_item(&query, name, items))
+            .filter(|item_match| item_match.score >= MIN_SCORE)
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Scores the query against an item name, reading the words not used by the name as
+    /// attributes.
+    fn score_item(
+        &self,
+        query: &[String],
+        name: &[String],
+        items: &[&SchemaItem],
+    ) -> Vec<SearchMatch> {
+        let mut scores = vec![0.0; query.len()];
+        let mut name_score = 0.0;
+        let mut pairs = Vec::new();
+
+        for (i, word) in query.iter().enumerate() {
+            for (j, name_word) in name.iter().enumerate() {
+                if let Some(score) = word_score(word, name_word) {
+                    pairs.push((score, i, j));
+                }
+            }
+        }
+
+        if pairs.is_empty() {
+            return Vec::new();
+        }
+
+        // Pairs each query word with at most one word of the name, best matches first.
+        pairs.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+
+        let mut used_names = vec![false; name.len()];
+
+        for (score, i, j) in pairs {
+            if scores[i] == 0.0 && !used_names[j] {
+                scores[i] = score;
+                used_names[j] = true;
+                name_score += score;
+            }
+        }
+
+        let leftover = (0..query.len()).filter(|i| scores[*i] == 0.0).collect::<Vec<_>>();
+        let mut attributes = Vec::new();
+        let mut number = None;
+        let mut k = 0;
+
+        while k < leftover.len() {
+            let words = leftover[k..].iter().map(|i| query[*i].as_str()).collect::<Vec<_>>();
+
+            if let Ok(n) = words[0].parse::<u32>() {
+                number = Some(n);
+                scores[leftover[k]] = 1.0;
+                k += 1;
+                continue;
+            }
+
+            match self.best_term(&words) {
+                Some((attribute, score, consumed)) => {
+                    attributes.push(attribute);
+
+                    for i in &leftover[k..k + consumed] {
+                        scores[*i] = score;
+                    }
+
+                    k += consumed;
+                },
+                None => k += 1,
+            }
+        }
+
+        let query_score = scores.iter().sum::<f32>() / query.len() as f32;
+        let coverage = name_score / name.len() as f32;
+        let score = (query_score + coverage) / 2.0;
+        let base = sku_from_attributes(&attributes);
+        let mut skus = items
+            .iter()
+            .map(|item| {
+                let mut sku = SKU {
+                    defindex: item.defindex as i32,
+                    ..base
+                };
+
+                if item.crate_series.is_some() || item.craft_class == Some(CraftClass::SupplyCrate) {
+                    sku.crate_number = number;
+                }
+
+                sku
+            })
+            .collect::<Vec<_>>();
+
+        narrow(&mut skus, self.schema, number);
+        skus.into_iter()
+            .map(|sku| SearchMatch {
+                sku,
+                score,
+            })
+            .collect()
+    }
+
+    /// Finds the term best matching the start of `words`, returning the attribute, the score
+    /// for each word used and the number of words used.
+    fn best_term(&self, words: &[&str]) -> Option<(Attribute, f32, usize)> {
+        let mut best: Option<(Attribute, f32, usize)> = None;
+
+        for term in &self.terms {
+            let mut total = 0.0;
+            let mut consumed = 0;
+
+            for (word, term_word) in words.iter().zip(&term.words) {
+                match word_score(word, term_word) {
+                    Some(score) => {
+                        total += score;
+                        consumed += 1;
+                    },
+                    None => break,
+                }
+            }
+
+            if consumed == 0 {
+                continue;
+            }
+
+            // Partial phrases e.g. "burning" for "Burning Flames" score a little lower.
+            let score = total / consumed as f32 *
+                (0.75 + 0.25 * consumed as f32 / term.words.len() as f32);
+            let is_better = best.map_or(true, |(_, best_score, best_consumed)| {
+                score * consumed as f32 > best_score * best_consumed as f32
+            });
+
+            if score >= 0.5 && is_better {
+                best = Some((term.attribute, score, consumed));
+            }
+        }
+
+        best
+    }
+}
+
+/// Builds a SKU without a defindex from the attributes read from a query.
+fn sku_from_attributes(attributes: &[Attribute]) -> SKU {
+    let mut sku = SKU::default();
+    let mut qualities = Vec::new();
+
+    for attribute in attributes {
+        match *attribute {
+            Attribute::NonCraftable => sku.craftable = false,
+            Attribute::Quality(quality) => qualities.push(quality),
+            Attribute::Particle(particle) => sku.particle = Some(particle),
+            Attribute::KillstreakTier(killstreak_tier) => sku.killstreak_tier = Some(killstreak_tier),
+            Attribute::Sheen(sheen) => sku.sheen = Some(sheen),
+            Attribute::Killstreaker(killstreaker) => sku.killstreaker = Some(killstreaker),
+            Attribute::Wear(wear) => sku.wear = Some(wear),
+            Attribute::Skin(skin) => sku.skin = Some(skin),
+            Attribute::Festivized => sku.festivized = true,
+            Attribute::Australium => sku.australium = true,
+        }
+    }
+
+    sku.quality = match qualities.as_slice() {
+        [] if sku.particle.is_some() => Quality::Unusual,
+        [] if sku.skin.is_some() => Quality::DecoratedWeapon,
+        [] => Quality::Unique,
+        // An elevated quality e.g. "Strange Unusual".
+        [Quality::Strange, quality, ..] if *quality != Quality::Strange => {
+            sku.strange = true;
+            *quality
+        },
+        [quality, ..] => *quality,
+    };
+    sku
+}
+
+/// Splits a name into lowercase words. Apostrophes and periods are dropped, and any other
+/// punctuation separates words.
+fn words(name: &str) -> Vec<String> {
+    name.chars()
+        .filter(|c| !matches!(c, '\'' | '.'))
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Scores how well a query word matches a word, from 0 to 1.
+fn word_score(query: &str, word: &str) -> Option<f32> {
+    if query == word {
+        return Some(1.0);
+    }
+
+    let query_len = query.chars().count();
+    let word_len = word.chars().count();
+
+    if query_len >= 2 && word.starts_with(query) {
+        return Some(0.5 + 0.4 * query_len as f32 / word_len as f32);
+    }
+
+    if query_len < 4 {
+        return None;
+    }
+
+    let max_distance = (query_len / 4).max(1);
+    let distance = levenshtein(query, word);
+
+    if distance <= max_distance {
+        return Some(0.8 - 0.15 * distance as f32);
+    }
+
+    // A prefix with a typo e.g. "lauch" for "launcher".
+    let prefix = word.chars().take(query_len).collect::<String>();
+
+    if query_len < word_len && levenshtein(query, &prefix) <= 1 {
+        return Some(0.5);
+    }
+
+    None
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != *b);
+
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Schema;
+    use crate::name::tests::schema;
+
+    fn top(query: &str) -> String {
+        let schema = schema();
+        let search = ItemSearch::new(&schema);
+
+        search.search(query, 1)
+            .first()
+            .map(|item_match| item_match.sku.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn finds_partial_names() {
+        assert_eq!(top("pro ks rocket launch"), "205;6;kt-3");
+        assert_eq!(top("unu team capt burning"), "378;5;u13");
+        assert_eq!(top("rocet laucher"), "205;6");
+    }
+
+    #[test]
+    fn understands_abbreviations() {
+        assert_eq!(top("strange unusual bf team captain"), "378;5;u13;strange");
+        assert_eq!(top("nc sks flame thrower"), "208;6;uncraftable;kt-2");
+        assert_eq!(top("strange warhawk war paint ft"), "16102;11;w3;pk102");
+        assert_eq!(top("mann co supply crate 2"), "5041;6;c2");
+    }
+
+    #[test]
+    fn ranks_matches() {
+        let schema = schema();
+        let search = ItemSearch::new(&schema);
+        let matches = search.search("shotgun", 10);
+
+        assert_eq!(matches[0].sku.to_string(), "199;6");
+        assert!(matches.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(search.search("shotgun", 1).len(), 1);
+        assert!(search.search("", 10).is_empty());
+        assert!(search.search("zzzz", 10).is_empty());
+    }
+
+    #[test]
+    fn searches_items_game_schemas() {
+        let schema = Schema::load_items_game("tests/fixtures/items_game.txt").unwrap();
+        let locale = Localization::load("tests/fixtures/tf_german.txt").unwrap();
+        let search = ItemSearch::with_localization(&schema, &locale);
+        let top = |search: &ItemSearch<Schema>, query: &str| search.search(query, 1)
+            .first()
+            .map(|item_match| item_match.sku.to_string())
+            .unwrap_or_default();
+
+        assert_eq!(top(&search, "strange raketenwerfer"), "205;11");
+        assert_eq!(top(&search, "unusual teamkapitän brennende flammen"), "378;5;u13");
+        assert_eq!(top(&search, "seltsam raketenwerfer"), "205;11");
+        assert_eq!(
+            top(&search, "professioneller killstreak seltsam raketenwerfer einsatzerprobt"),
+            "205;11;w3;kt-3",
+        );
+        assert_eq!(top(&ItemSearch::new(&locale.localize(&schema)), "strange raketenwerfer"), "205;11");
+        assert!(ItemSearch::new(&schema).search("raketenwerfer", 1).is_empty());
+    }
+
+    #[test]
+    fn scores_words() {
+        assert_eq!(word_score("rocket", "rocket"), Some(1.0));
+        assert!(word_score("launch", "launcher").is_some());
+        assert!(word_score("laucher", "launcher").is_some());
+        assert_eq!(word_score("x", "launcher"), None);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+}
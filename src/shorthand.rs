@@ -0,0 +1,185 @@
+//! Shorthand item names as written in trade chats e.g. "Pro KS Aus Rocket Launcher".
+
+use crate::SKU;
+use crate::error::NameParseError;
+use crate::locale::Word;
+use crate::name::{English, Vocabulary};
+use crate::schema::ItemSchema;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use tf2_enum::{KillstreakTier, Quality, Wear};
+
+/// The abbreviations used in shorthand names. Anything without an abbreviation is written in
+/// full.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::shorthand::Abbreviations;
+/// use tf2_enum::Quality;
+///
+/// let mut abbreviations = Abbreviations::default();
+///
+/// abbreviations.qualities.insert(Quality::Strange, "Str".into());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviations {
+    /// Abbreviations for qualities e.g. "Unu" for Unusual.
+    pub qualities: HashMap<Quality, String>,
+    /// Abbreviations for killstreak tiers e.g. "Pro KS" for Professional Killstreak.
+    pub killstreak_tiers: HashMap<KillstreakTier, String>,
+    /// Abbreviations for wears e.g. "FN" for Factory New. Abbreviated wears go before the item
+    /// name.
+    pub wears: HashMap<Wear, String>,
+    /// Abbreviations for particle effects by ID e.g. "BF" for Burning Flames.
+    pub particles: HashMap<u32, String>,
+    /// The abbreviation for "Non-Craftable".
+    pub non_craftable: String,
+    /// The abbreviation for "Festivized".
+    pub festivized: String,
+    /// The abbreviation for "Australium".
+    pub australium: String,
+}
+
+impl Default for Abbreviations {
+    /// Creates the abbreviations commonly used in trade chats.
+    fn default() -> Self {
+        Self {
+            qualities: [
+                (Quality::Unusual, "Unu"),
+                (Quality::Genuine, "Gen"),
+                (Quality::Vintage, "Vin"),
+            ].into_iter().map(|(key, value)| (key, value.to_string())).collect(),
+            killstreak_tiers: [
+                (KillstreakTier::Killstreak, "KS"),
+                (KillstreakTier::Specialized, "Spec KS"),
+                (KillstreakTier::Professional, "Pro KS"),
+            ].into_iter().map(|(key, value)| (key, value.to_string())).collect(),
+            wears: [
+                (Wear::FactoryNew, "FN"),
+                (Wear::MinimalWear, "MW"),
+                (Wear::FieldTested, "FT"),
+                (Wear::WellWorn, "WW"),
+                (Wear::BattleScarred, "BS"),
+            ].into_iter().map(|(key, value)| (key, value.to_string())).collect(),
+            particles: [
+                (13, "BF"),
+                (14, "SF"),
+            ].into_iter().map(|(key, value)| (key, value.to_string())).collect(),
+            non_craftable: "Non-Craft".into(),
+            festivized: "Fest".into(),
+            australium: "Aus".into(),
+        }
+    }
+}
+
+impl Vocabulary for Abbreviations {
+    fn word(&self, word: Word) -> Cow<'_, str> {
+        let abbreviation = match word {
+            Word::NonCraftable => Some(&self.non_craftable),
+            Word::Quality(quality) => self.qualities.get(&quality),
+            Word::KillstreakTier(killstreak_tier) => self.killstreak_tiers.get(&killstreak_tier),
+            Word::Wear(wear) => self.wears.get(&wear),
+            Word::Festivized => Some(&self.festivized),
+            Word::Australium => Some(&self.australium),
+            Word::Sheen(_) |
+            Word::Killstreaker(_) |
+            Word::The => None,
+        };
+
+        abbreviation
+            .map(|abbreviation| Cow::Borrowed(abbreviation.as_str()))
+            .unwrap_or_else(|| word.english())
+    }
+
+    fn particle_name(&self, particle: u32) -> Option<&str> {
+        self.particles.get(&particle).map(String::as_str)
+    }
+
+    fn wear_first(&self) -> bool {
+        true
+    }
+}
+
+impl SKU {
+    /// Gets the shorthand name of the item e.g. "Strange Unu BF Team Captain".
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::shorthand::Abbreviations;
+    ///
+    /// let sku = "205;11;kt-3;australium;uncraftable".parse::<SKU>().unwrap();
+    ///
+    /// assert_eq!(
+    ///     sku.shorthand(&Schema::default(), &Abbreviations::default()),
+    ///     "Non-Craft Strange Pro KS Aus Item #205",
+    /// );
+    /// ```
+    pub fn shorthand<S: ItemSchema + ?Sized>(
+        &self,
+        schema: &S,
+        abbreviations: &Abbreviations,
+    ) -> String {
+        self.name_in(schema, abbreviations)
+    }
+
+    /// Parses a shorthand name. Both abbreviations and full words are accepted, so full names
+    /// also parse. Matching is case insensitive.
+    ///
+    /// # Errors
+    /// The same as [`SKU::from_name`].
+    pub fn from_shorthand<S: ItemSchema + ?Sized>(
+        name: &str,
+        schema: &S,
+        abbreviations: &Abbreviations,
+    ) -> Result<SKU, NameParseError> {
+        SKU::from_name_in(name, schema, &[abbreviations, &English])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn shorthand(sku: &str) -> String {
+        sku.parse::<SKU>().unwrap().shorthand(&schema(), &Abbreviations::default())
+    }
+
+    fn parse(name: &str) -> String {
+        SKU::from_shorthand(name, &schema(), &Abbreviations::default()).unwrap().to_string()
+    }
+
+    #[test]
+    fn formats_shorthand() {
+        assert_eq!(shorthand("205;11;kt-3;australium"), "Strange Pro KS Aus Rocket Launcher");
+        assert_eq!(shorthand("378;5;u13;strange"), "Strange Unu BF Team Captain");
+        assert_eq!(shorthand("378;5;u702"), "Isotope Team Captain");
+        assert_eq!(shorthand("16102;15;w1;pk102"), "FN Warhawk War Paint");
+        assert_eq!(shorthand("208;6;uncraftable;kt-2;festive"), "Non-Craft Spec KS Fest Flame Thrower");
+    }
+
+    #[test]
+    fn parses_shorthand() {
+        assert_eq!(parse("Pro KS Aus Rocket Launcher"), "205;6;australium;kt-3");
+        assert_eq!(parse("strange unu bf team captain"), "378;5;u13;strange");
+        assert_eq!(parse("Non-Craft Spec KS Flame Thrower"), "208;6;uncraftable;kt-2");
+        assert_eq!(parse("FN Warhawk War Paint"), "16102;15;w1;pk102");
+        // Full names and wears still parse.
+        assert_eq!(parse("Burning Flames Team Captain"), "378;5;u13");
+        assert_eq!(parse("Warhawk Rocket Launcher (Field-Tested)"), "205;15;w3;pk102");
+    }
+
+    #[test]
+    fn uses_custom_abbreviations() {
+        let mut abbreviations = Abbreviations::default();
+
+        abbreviations.qualities.insert(Quality::Strange, "Str".into());
+
+        let sku = "205;11".parse::<SKU>().unwrap();
+        let name = sku.shorthand(&schema(), &abbreviations);
+
+        assert_eq!(name, "Str Rocket Launcher");
+        assert_eq!(SKU::from_shorthand(&name, &schema(), &abbreviations), Ok(sku));
+    }
+}
@@ -0,0 +1,61 @@
+//! Schema tables compiled into the binary, generated by [`codegen`](crate::codegen) from a schema
+//! snapshot. Lookups require no loading at runtime.
+//!
+//! The generated file is included from the path in the `TF2_SKU_STATIC_SCHEMA` environment
+//! variable at build time. Without it, the tables are empty and the build script warns.
+
+use crate::schema::{ItemSchema, SchemaItem};
+
+#[cfg(not(test))]
+include!(concat!(env!("OUT_DIR"), "/static_schema.rs"));
+// Tests run against tables generated from the items_game fixture.
+#[cfg(test)]
+include!("../tests/fixtures/static_schema.rs");
+
+/// An [`ItemSchema`] backed by the compiled-in tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StaticSchema;
+
+impl ItemSchema for StaticSchema {
+    fn item(&self, defindex: u32) -> Option<&SchemaItem> {
+        ITEMS.get(&defindex)
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &SchemaItem> + '_> {
+        Box::new(ITEMS.values())
+    }
+
+    fn particle_name(&self, particle: u32) -> Option<&str> {
+        PARTICLES.get(&particle).copied()
+    }
+
+    fn particles(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+        Box::new(PARTICLES.entries().map(|(id, name)| (*id, *name)))
+    }
+
+    fn paint_kit_name(&self, paint_kit: u32) -> Option<&str> {
+        PAINT_KITS.get(&paint_kit).copied()
+    }
+
+    fn paint_kits(&self) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+        Box::new(PAINT_KITS.entries().map(|(id, name)| (*id, *name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tf2_enum::Quality;
+
+    #[test]
+    fn looks_up_generated_tables() {
+        let schema = StaticSchema;
+        let item = schema.item(205).unwrap();
+
+        assert_eq!(item.item_name, "#TF_Weapon_RocketLauncher");
+        assert_eq!(item.item_quality, Quality::Unique);
+        assert_eq!(schema.particle_name(13), Some("#Attrib_Particle13"));
+        assert_eq!(schema.items().count(), ITEMS.len());
+        assert!(schema.item(1).is_none());
+    }
+}
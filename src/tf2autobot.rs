@@ -0,0 +1,292 @@
+//! Models for tf2autobot's `pricelist.json`, keyed by SKU.
+//!
+//! Fields not modeled here, such as those added by newer versions of tf2autobot, are kept in
+//! each entry's `extra` map and written back unchanged.
+
+use crate::SKU;
+use crate::currencies::Currencies;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// A pricelist, keyed by SKU.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::SKU;
+/// use tf2_sku::tf2autobot::Pricelist;
+///
+/// let json = r#"{
+///     "5021;6": {
+///         "enabled": true,
+///         "autoprice": true,
+///         "min": 0,
+///         "max": 1,
+///         "intent": 2,
+///         "buy": { "keys": 0, "metal": 60.33 },
+///         "sell": { "keys": 0, "metal": 60.44 },
+///         "customField": 1
+///     }
+/// }"#;
+/// let pricelist = serde_json::from_str::<Pricelist>(json).unwrap();
+/// let entry = &pricelist.entries[&"5021;6".parse::<SKU>().unwrap()];
+///
+/// assert_eq!(entry.max, 1);
+/// assert_eq!(entry.extra["customField"], 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Pricelist {
+    /// The entries.
+    pub entries: BTreeMap<SKU, PricelistEntry>,
+}
+
+/// An entry in a pricelist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PricelistEntry {
+    /// The SKU, repeated from the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sku: Option<SKU>,
+    /// The name of the item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether the bot trades the item.
+    pub enabled: bool,
+    /// Whether the price is updated from the bot's price source.
+    pub autoprice: bool,
+    /// The least the bot keeps in stock.
+    pub min: i64,
+    /// The most the bot keeps in stock, or -1 for no limit.
+    pub max: i64,
+    /// Whether the bot buys, sells or does both.
+    pub intent: Intent,
+    /// The buying price.
+    pub buy: Currencies,
+    /// The selling price.
+    pub sell: Currencies,
+    /// Whether the item's listing is promoted on backpack.tf, 1 if it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted: Option<u8>,
+    /// The group the entry belongs to e.g. "all".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Notes added to the item's listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<Note>,
+    /// Whether the price was set by hand for part of the stock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_partial_priced: Option<bool>,
+    /// When the price was last updated, as a Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+    /// Fields not modeled above.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// What a bot does with an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Intent {
+    /// Only buys the item.
+    Buy,
+    /// Only sells the item.
+    Sell,
+    /// Buys and sells the item.
+    Bank,
+}
+
+impl TryFrom<u8> for Intent {
+    type Error = String;
+
+    fn try_from(intent: u8) -> Result<Self, Self::Error> {
+        match intent {
+            0 => Ok(Intent::Buy),
+            1 => Ok(Intent::Sell),
+            2 => Ok(Intent::Bank),
+            _ => Err(format!("Invalid intent: {intent}")),
+        }
+    }
+}
+
+impl From<Intent> for u8 {
+    fn from(intent: Intent) -> Self {
+        match intent {
+            Intent::Buy => 0,
+            Intent::Sell => 1,
+            Intent::Bank => 2,
+        }
+    }
+}
+
+/// Notes added to an item's listings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    /// The note on buy listings.
+    #[serde(default)]
+    pub buying: Option<String>,
+    /// The note on sell listings.
+    #[serde(default)]
+    pub selling: Option<String>,
+}
+
+/// How [`Pricelist::merge`] settles entries in both pricelists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeStrategy {
+    /// Keeps the entry already in the pricelist.
+    KeepOurs,
+    /// Takes the entry from the other pricelist.
+    TakeTheirs,
+    /// Takes the entry updated last, by `time`. Entries without a time are the oldest, and ties
+    /// keep the entry already in the pricelist.
+    Newest,
+}
+
+/// The differences between two pricelists. SKUs are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PricelistDiff {
+    /// SKUs only in the new pricelist.
+    pub added: Vec<SKU>,
+    /// SKUs only in the old pricelist.
+    pub removed: Vec<SKU>,
+    /// SKUs in both pricelists with different entries.
+    pub changed: Vec<SKU>,
+}
+
+impl PricelistDiff {
+    /// Compares `old` to `new`.
+    pub fn new(old: &Pricelist, new: &Pricelist) -> Self {
+        let mut diff = Self::default();
+
+        for (sku, old_entry) in &old.entries {
+            match new.entries.get(sku) {
+                None => diff.removed.push(*sku),
+                Some(new_entry) if new_entry != old_entry => diff.changed.push(*sku),
+                Some(_) => {},
+            }
+        }
+
+        diff.added = new.entries
+            .keys()
+            .filter(|sku| !old.entries.contains_key(sku))
+            .copied()
+            .collect();
+        diff
+    }
+
+    /// Checks whether there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Pricelist {
+    /// Merges the entries of `other` into this pricelist. Entries only in `other` are added, and
+    /// entries in both are settled by `strategy`. Nothing is removed.
+    ///
+    /// Returns the changes made to this pricelist.
+    pub fn merge(&mut self, other: &Pricelist, strategy: MergeStrategy) -> PricelistDiff {
+        let old = self.clone();
+
+        for (sku, theirs) in &other.entries {
+            let Some(ours) = self.entries.get_mut(sku) else {
+                self.entries.insert(*sku, theirs.clone());
+                continue;
+            };
+            let take_theirs = match strategy {
+                MergeStrategy::KeepOurs => false,
+                MergeStrategy::TakeTheirs => true,
+                MergeStrategy::Newest => theirs.time > ours.time,
+            };
+
+            if take_theirs {
+                *ours = theirs.clone();
+            }
+        }
+
+        PricelistDiff::new(&old, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pricelist(path: &str) -> Pricelist {
+        let json = std::fs::read_to_string(path).unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn sku(sku: &str) -> SKU {
+        sku.parse().unwrap()
+    }
+
+    #[test]
+    fn reads_pricelists() {
+        let pricelist = pricelist("tests/fixtures/pricelist.json");
+        let entry = &pricelist.entries[&sku("205;11;kt-3")];
+
+        assert_eq!(pricelist.entries.len(), 3);
+        assert_eq!(entry.intent, Intent::Sell);
+        assert_eq!(entry.buy, Currencies::new(0, 3 * Currencies::ONE_REF + 3 * Currencies::ONE_SCRAP));
+        assert_eq!(entry.sell, Currencies::new(1, 0));
+        assert_eq!(entry.note.as_ref().unwrap().selling.as_deref(), Some("Fast trades!"));
+        assert_eq!(entry.extra["customEntryField"], serde_json::json!({ "nested": [1, 2] }));
+    }
+
+    #[test]
+    fn writes_unknown_fields_back() {
+        let json = std::fs::read_to_string("tests/fixtures/pricelist.json").unwrap();
+        let pricelist = serde_json::from_str::<Pricelist>(&json).unwrap();
+        let written = serde_json::to_value(&pricelist).unwrap();
+
+        assert_eq!(written, serde_json::from_str::<Value>(&json).unwrap());
+    }
+
+    #[test]
+    fn diffs_pricelists() {
+        let old = pricelist("tests/fixtures/pricelist.json");
+        let mut new = old.clone();
+
+        new.entries.remove(&sku("5021;6"));
+        new.entries.get_mut(&sku("205;11;kt-3")).unwrap().max = 5;
+        new.entries.insert(sku("378;6"), old.entries[&sku("5021;6")].clone());
+
+        let diff = PricelistDiff::new(&old, &new);
+
+        assert_eq!(diff.added, [sku("378;6")]);
+        assert_eq!(diff.removed, [sku("5021;6")]);
+        assert_eq!(diff.changed, [sku("205;11;kt-3")]);
+        assert!(PricelistDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn merges_pricelists() {
+        let ours = pricelist("tests/fixtures/pricelist.json");
+        let mut theirs = Pricelist::default();
+        let mut newer = ours.entries[&sku("205;11;kt-3")].clone();
+        let mut older = ours.entries[&sku("5021;6")].clone();
+
+        newer.time = newer.time.map(|time| time + 60);
+        newer.max = 5;
+        older.time = Some(0);
+        older.max = 5;
+        theirs.entries.insert(sku("205;11;kt-3"), newer);
+        theirs.entries.insert(sku("5021;6"), older);
+        theirs.entries.insert(sku("378;6"), ours.entries[&sku("5021;6")].clone());
+
+        let mut merged = ours.clone();
+        let diff = merged.merge(&theirs, MergeStrategy::Newest);
+
+        assert_eq!(diff.added, [sku("378;6")]);
+        assert_eq!(diff.changed, [sku("205;11;kt-3")]);
+        assert_eq!(merged.entries[&sku("5021;6")].max, ours.entries[&sku("5021;6")].max);
+
+        let mut merged = ours.clone();
+
+        assert_eq!(merged.merge(&theirs, MergeStrategy::KeepOurs).changed, []);
+        assert_eq!(merged.merge(&theirs, MergeStrategy::TakeTheirs).changed.len(), 2);
+    }
+}
@@ -0,0 +1,361 @@
+//! Links to items on backpack.tf, marketplace.tf and the Steam Community Market.
+//!
+//! backpack.tf identifies an item by its quality, its name without the quality or effect, its
+//! craftability and a price index, which holds what the name leaves out (see
+//! [`SKU::price_index`]).
+
+use crate::SKU;
+use crate::error::{NameParseError, UrlError};
+use crate::name::English;
+use crate::price_index::{apply_price_index, parse_price_index};
+use crate::schema::ItemSchema;
+use tf2_enum::Quality;
+
+const BACKPACK_TF: &str = "https://backpack.tf";
+const MARKETPLACE_TF: &str = "https://marketplace.tf";
+const STEAM_COMMUNITY: &str = "https://steamcommunity.com";
+/// The app ID of Team Fortress 2.
+const APP_ID: &str = "440";
+
+/// Builds the backpack.tf stats URL of an item e.g.
+/// `https://backpack.tf/stats/Strange/Rocket%20Launcher/Tradable/Craftable`.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{SKU, Schema};
+///
+/// let sku = "205;5;u13;uncraftable".parse::<SKU>().unwrap();
+///
+/// assert_eq!(
+///     tf2_sku::url::backpack_tf_stats(&sku, &Schema::default()),
+///     "https://backpack.tf/stats/Unusual/Item%20%23205/Tradable/Non-Craftable/13",
+/// );
+/// ```
+pub fn backpack_tf_stats<S: ItemSchema + ?Sized>(sku: &SKU, schema: &S) -> String {
+    let mut url = format!(
+        "{BACKPACK_TF}/stats/{}/{}/Tradable/{}",
+        encode(&sku.quality.to_string()),
+        encode(&backpack_tf_name(sku, schema)),
+        if sku.craftable { "Craftable" } else { "Non-Craftable" },
+    );
+
+    if let Some(price_index) = sku.price_index() {
+        url.push('/');
+        url.push_str(&price_index);
+    }
+
+    url
+}
+
+/// Builds the backpack.tf classifieds search URL of an item e.g.
+/// `https://backpack.tf/classifieds?item=Rocket%20Launcher&quality=11&tradable=1&craftable=1`.
+pub fn backpack_tf_classifieds<S: ItemSchema + ?Sized>(sku: &SKU, schema: &S) -> String {
+    let mut url = format!(
+        "{BACKPACK_TF}/classifieds?item={}&quality={}&tradable=1&craftable={}",
+        encode(&backpack_tf_name(sku, schema)),
+        sku.quality as u32,
+        if sku.craftable { "1" } else { "-1" },
+    );
+
+    if let Some(price_index) = sku.price_index() {
+        url.push_str("&priceindex=");
+        url.push_str(&price_index);
+    }
+
+    url
+}
+
+/// Builds the marketplace.tf URL of an item e.g. `https://marketplace.tf/items/tf2/205;11`.
+pub fn marketplace_tf(sku: &SKU) -> String {
+    format!("{MARKETPLACE_TF}/items/tf2/{sku}")
+}
+
+/// Builds the Steam Community Market listing URL of an item e.g.
+/// `https://steamcommunity.com/market/listings/440/Strange%20Rocket%20Launcher`.
+pub fn steam_market<S: ItemSchema + ?Sized>(sku: &SKU, schema: &S) -> String {
+    format!(
+        "{STEAM_COMMUNITY}/market/listings/{APP_ID}/{}",
+        encode(&sku.market_hash_name(schema)),
+    )
+}
+
+/// Parses a link to an item on backpack.tf, marketplace.tf or the Steam Community Market into
+/// a SKU. The scheme and "www." are optional.
+///
+/// Steam Community Market links can't tell attributes left out of market names, such as
+/// effects, so those are left unset.
+///
+/// # Errors
+/// Returns [`UrlError::Unsupported`] for links to anything else, or an error describing which
+/// part of the link couldn't be understood. Names matching more than one item return
+/// [`NameParseError::Ambiguous`].
+///
+/// # Examples
+/// ```
+/// use tf2_sku::{SKU, Schema};
+///
+/// let sku = tf2_sku::url::parse("https://marketplace.tf/items/tf2/205;11;kt-3", &Schema::default()).unwrap();
+///
+/// assert_eq!(sku.to_string(), "205;11;kt-3");
+/// ```
+pub fn parse<S: ItemSchema + ?Sized>(url: &str, schema: &S) -> Result<SKU, UrlError> {
+    let unsupported = || UrlError::Unsupported(url.to_string());
+    let trimmed = url.trim();
+    let trimmed = trimmed.split_once("://").map_or(trimmed, |(_, rest)| rest);
+    let trimmed = trimmed.strip_prefix("www.").unwrap_or(trimmed);
+    let trimmed = trimmed.split_once('#').map_or(trimmed, |(rest, _)| rest);
+    let (host, rest) = trimmed.split_once('/').unwrap_or((trimmed, ""));
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(decode)
+        .collect::<Vec<_>>();
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match (host.to_ascii_lowercase().as_str(), segments.as_slice()) {
+        ("backpack.tf", ["stats", quality, name, _tradable, craftable, rest @ ..]) => {
+            let quality = parse_quality(quality)?;
+            let craftable = !craftable.eq_ignore_ascii_case("Non-Craftable");
+
+            from_backpack_tf(schema, quality, name, craftable, rest.first().copied())
+        },
+        ("backpack.tf", ["stats", ..]) => Err(UrlError::MissingPart("craftability")),
+        ("backpack.tf", ["classifieds"]) => {
+            let params = query
+                .split('&')
+                .filter_map(|param| param.split_once('='))
+                .map(|(key, value)| (key, decode(&value.replace('+', " "))))
+                .collect::<Vec<_>>();
+            let param = |key: &str| params
+                .iter()
+                .find(|(param, _)| *param == key)
+                .map(|(_, value)| value.as_str());
+            let name = param("item").ok_or(UrlError::MissingPart("item"))?;
+            let quality = param("quality")
+                .map(parse_quality)
+                .transpose()?
+                .unwrap_or(Quality::Unique);
+            let craftable = !matches!(param("craftable"), Some("-1" | "0"));
+
+            from_backpack_tf(schema, quality, name, craftable, param("priceindex"))
+        },
+        ("marketplace.tf", ["items", "tf2", sku, ..]) => Ok(sku.parse()?),
+        ("steamcommunity.com", ["market", "listings", APP_ID, name]) => {
+            let mut skus = SKU::from_market_hash_name(name, schema)?;
+
+            if skus.len() == 1 {
+                Ok(skus.remove(0))
+            } else {
+                Err(NameParseError::Ambiguous(skus).into())
+            }
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+/// Gets the name backpack.tf uses for an item, which leaves out the quality, effect,
+/// craftability and anything in the price index.
+fn backpack_tf_name<S: ItemSchema + ?Sized>(sku: &SKU, schema: &S) -> String {
+    let base = SKU {
+        quality: Quality::Unique,
+        // Elevated qualities are named by the item e.g. "Strange Team Captain" in Unusual.
+        strange: false,
+        craftable: true,
+        particle: None,
+        sheen: None,
+        killstreaker: None,
+        target_defindex: None,
+        output_defindex: None,
+        output_quality: None,
+        craft_number: None,
+        crate_number: None,
+        ..*sku
+    };
+    let name = base.name_in(schema, &English);
+    let name = match schema.sku_item(sku) {
+        Some(item) if item.proper_name => name.strip_prefix("The ").map(str::to_string).unwrap_or(name),
+        _ => name,
+    };
+
+    if sku.strange && sku.quality != Quality::Strange {
+        format!("Strange {name}")
+    } else {
+        name
+    }
+}
+
+/// Builds a SKU from the parts of a backpack.tf URL.
+fn from_backpack_tf<S: ItemSchema + ?Sized>(
+    schema: &S,
+    quality: Quality,
+    name: &str,
+    craftable: bool,
+    price_index: Option<&str>,
+) -> Result<SKU, UrlError> {
+    // Put an elevated quality where it goes in a full name e.g. "Strange Unusual".
+    let name = match name.strip_prefix("Strange ") {
+        Some(rest) if quality != Quality::Strange => format!("Strange {quality} {rest}"),
+        _ => format!("{quality} {name}"),
+    };
+    let price_index = price_index.unwrap_or_default();
+    let numbers = parse_price_index(price_index)
+        .ok_or_else(|| UrlError::InvalidPriceIndex(price_index.to_string()))?;
+    let mut skus = SKU::candidates_in(&name, schema, &[&English])?;
+
+    for sku in &mut skus {
+        sku.craftable = craftable;
+        apply_price_index(sku, schema, &numbers)
+            .ok_or_else(|| UrlError::InvalidPriceIndex(price_index.to_string()))?;
+    }
+
+    let crate_number = skus.iter().find_map(|sku| sku.crate_number);
+
+    crate::name::narrow(&mut skus, schema, crate_number);
+    skus.dedup();
+
+    if skus.len() == 1 {
+        Ok(skus.remove(0))
+    } else {
+        Err(NameParseError::Ambiguous(skus).into())
+    }
+}
+
+/// Parses a quality by its name e.g. "Strange" or its ID e.g. "11".
+fn parse_quality(quality: &str) -> Result<Quality, UrlError> {
+    quality.parse::<u32>()
+        .ok()
+        .and_then(|id| Quality::try_from(id).ok())
+        .or_else(|| quality.parse().ok())
+        .ok_or_else(|| UrlError::UnknownQuality(quality.to_string()))
+}
+
+/// Percent-encodes a URL path segment or query value.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            },
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
+
+/// Decodes a percent-encoded value. Invalid escapes are kept as they are.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            },
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn round_trip(url: &str) -> String {
+        parse(url, &schema()).unwrap().to_string()
+    }
+
+    #[test]
+    fn builds_backpack_tf_urls() {
+        let sku = "378;5;u13;strange".parse::<SKU>().unwrap();
+
+        assert_eq!(
+            backpack_tf_stats(&sku, &schema()),
+            "https://backpack.tf/stats/Unusual/Strange%20Team%20Captain/Tradable/Craftable/13",
+        );
+
+        let sku = "205;11;kt-3;australium;uncraftable".parse::<SKU>().unwrap();
+
+        assert_eq!(
+            backpack_tf_classifieds(&sku, &schema()),
+            "https://backpack.tf/classifieds?item=Professional%20Killstreak%20Australium%20Rocket%20Launcher&quality=11&tradable=1&craftable=-1",
+        );
+
+        let sku = "6527;6;kt-3;td-205".parse::<SKU>().unwrap();
+
+        assert_eq!(
+            backpack_tf_stats(&sku, &schema()),
+            "https://backpack.tf/stats/Unique/Professional%20Killstreak%20Kit/Tradable/Craftable/3-205",
+        );
+    }
+
+    #[test]
+    fn builds_market_urls() {
+        let sku = "20007;6;od-205;oq-14".parse::<SKU>().unwrap();
+
+        assert_eq!(marketplace_tf(&sku), "https://marketplace.tf/items/tf2/20007;6;od-205;oq-14");
+        assert_eq!(
+            steam_market(&sku, &schema()),
+            "https://steamcommunity.com/market/listings/440/Collector%27s%20Rocket%20Launcher%20Chemistry%20Set",
+        );
+    }
+
+    #[test]
+    fn parses_urls() {
+        assert_eq!(round_trip("https://backpack.tf/stats/Unusual/Strange%20Team%20Captain/Tradable/Craftable/13"), "378;5;u13;strange");
+        assert_eq!(round_trip("backpack.tf/stats/Strange/Rocket Launcher/Tradable/Non-Craftable"), "205;11;uncraftable");
+        assert_eq!(round_trip("https://backpack.tf/classifieds?item=Rocket+Launcher&quality=11&tradable=1&craftable=1"), "205;11");
+        assert_eq!(round_trip("https://www.marketplace.tf/items/tf2/205;11;kt-3"), "205;11;kt-3");
+        assert_eq!(round_trip("https://steamcommunity.com/market/listings/440/Mann%20Co.%20Supply%20Crate%20Series%20%232"), "5041;6;c2");
+        assert_eq!(parse("https://example.com/items/205", &schema()), Err(UrlError::Unsupported("https://example.com/items/205".into())));
+    }
+
+    #[test]
+    fn returns_ambiguous_items() {
+        let sku = "20007;6;od-205;oq-14".parse::<SKU>().unwrap();
+
+        // Both chemistry sets are named "Chemistry Set".
+        match parse(&backpack_tf_stats(&sku, &schema()), &schema()) {
+            Err(UrlError::Name(NameParseError::Ambiguous(skus))) => {
+                assert_eq!(skus.len(), 2);
+                assert!(skus.contains(&sku));
+            },
+            result => panic!("expected ambiguous items, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn round_trips_urls() {
+        for sku in [
+            "378;5;u702;strange",
+            "205;15;uncraftable;w3;pk102",
+            "5022;6;c1",
+            "6527;6;kt-3;td-205",
+            "6522;6;td-205",
+            "208;6;australium;kt-2;festive",
+        ] {
+            let parsed = sku.parse::<SKU>().unwrap();
+
+            assert_eq!(round_trip(&backpack_tf_stats(&parsed, &schema())), sku);
+            assert_eq!(round_trip(&backpack_tf_classifieds(&parsed, &schema())), sku);
+            assert_eq!(round_trip(&marketplace_tf(&parsed)), sku);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Valuing inventories from a price table.
+
+use crate::SKU;
+use crate::currencies::Currencies;
+use crate::price_table::PriceTable;
+use crate::schema::ItemSchema;
+use std::collections::{BTreeMap, HashMap};
+use tf2_enum::{KillstreakTier, Quality};
+
+/// Which side of a price items are valued at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PriceSide {
+    /// The buying price, what the items would be bought back for.
+    #[default]
+    Buy,
+    /// The selling price.
+    Sell,
+}
+
+/// How an item was priced. Rules are tried in the order listed, and each later rule gives a
+/// price no higher than the item is worth, as it drops value-adding attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PriceRule {
+    /// The SKU is in the price table.
+    Exact,
+    /// The SKU is a key or metal, valued at face value.
+    Currency,
+    /// The SKU is priced without its craft number, paint, spells and strange parts.
+    WithoutExtras,
+    /// The SKU is priced without its extras and killstreak.
+    WithoutKillstreak,
+}
+
+/// The value of the items of one SKU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ItemValuation {
+    /// The number of items.
+    pub count: u32,
+    /// The price of each item. After [`Valuation::merge`], this is the price from the valuation
+    /// merged into.
+    pub price: Currencies,
+    /// The value of all the items, which stays the sum of their prices when valuations priced
+    /// differently are merged.
+    pub value: Currencies,
+    /// The rule that priced the item.
+    pub rule: PriceRule,
+    /// The SKU the price was taken from.
+    pub priced_as: SKU,
+}
+
+/// The value of an inventory. Totals add keys and metal separately, without trading one for the
+/// other.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Valuation {
+    /// The total value of the priced items.
+    pub total: Currencies,
+    /// The priced items, by SKU.
+    pub items: BTreeMap<SKU, ItemValuation>,
+    /// The number of items of each SKU no rule could price.
+    pub unpriced: BTreeMap<SKU, u32>,
+    /// The value of the priced items by quality.
+    pub by_quality: BTreeMap<Quality, Currencies>,
+    /// The value of the priced items by schema item class e.g. "tf_wearable". Items not in the
+    /// schema are under `None`.
+    pub by_item_class: BTreeMap<Option<String>, Currencies>,
+    /// The value of the priced items by killstreak tier.
+    pub by_killstreak_tier: BTreeMap<Option<KillstreakTier>, Currencies>,
+}
+
+impl Valuation {
+    /// Values an inventory, given as the number of items of each SKU.
+    ///
+    /// # Examples
+    /// ```
+    /// use tf2_sku::{SKU, Schema};
+    /// use tf2_sku::price_table::{ItemPrice, PriceTable};
+    /// use tf2_sku::valuation::{PriceRule, PriceSide, Valuation};
+    /// use std::collections::HashMap;
+    ///
+    /// let hat = "378;6".parse::<SKU>().unwrap();
+    /// let painted_hat = "378;6;p8421376".parse::<SKU>().unwrap();
+    /// let refined = "5002;6".parse::<SKU>().unwrap();
+    /// let mut prices = PriceTable::new();
+    ///
+    /// prices.insert(hat, ItemPrice {
+    ///     buy: "1.33 ref".parse().unwrap(),
+    ///     sell: "1.55 ref".parse().unwrap(),
+    /// });
+    ///
+    /// let inventory = HashMap::from([(painted_hat, 2), (refined, 3)]);
+    /// let valuation = Valuation::new(&inventory, &prices, &Schema::default(), PriceSide::Buy);
+    ///
+    /// assert_eq!(valuation.total.to_string(), "5.66 ref");
+    /// assert_eq!(valuation.items[&painted_hat].rule, PriceRule::WithoutExtras);
+    /// ```
+    pub fn new<S: ItemSchema + ?Sized>(
+        inventory: &HashMap<SKU, u32>,
+        prices: &PriceTable,
+        schema: &S,
+        side: PriceSide,
+    ) -> Self {
+        let mut valuation = Self::default();
+
+        for (sku, count) in inventory {
+            if *count == 0 {
+                continue;
+            }
+
+            let Some((price, rule, priced_as)) = price(sku, prices, side) else {
+                *valuation.unpriced.entry(*sku).or_default() += count;
+                continue;
+            };
+            let value = price * i64::from(*count);
+            let item_class = schema
+                .sku_item(sku)
+                .map(|item| item.item_class.to_string());
+
+            valuation.total += value;
+            *valuation.by_quality.entry(sku.quality).or_default() += value;
+            *valuation.by_item_class.entry(item_class).or_default() += value;
+            *valuation.by_killstreak_tier.entry(sku.killstreak_tier).or_default() += value;
+            valuation.items.insert(*sku, ItemValuation {
+                count: *count,
+                price,
+                value,
+                rule,
+                priced_as,
+            });
+        }
+
+        valuation
+    }
+
+    /// Adds the items and totals of another valuation, such as to report on several inventories
+    /// together. Items of the same SKU keep this valuation's price, and their values are added.
+    pub fn merge(&mut self, other: &Valuation) {
+        self.total += other.total;
+
+        for (sku, item) in &other.items {
+            self.items
+                .entry(*sku)
+                .and_modify(|ours| {
+                    ours.count += item.count;
+                    ours.value += item.value;
+                })
+                .or_insert(*item);
+        }
+
+        for (sku, count) in &other.unpriced {
+            *self.unpriced.entry(*sku).or_default() += count;
+        }
+
+        for (quality, value) in &other.by_quality {
+            *self.by_quality.entry(*quality).or_default() += *value;
+        }
+
+        for (item_class, value) in &other.by_item_class {
+            *self.by_item_class.entry(item_class.clone()).or_default() += *value;
+        }
+
+        for (killstreak_tier, value) in &other.by_killstreak_tier {
+            *self.by_killstreak_tier.entry(*killstreak_tier).or_default() += *value;
+        }
+    }
+
+    /// Gets the number of items priced, by the rule that priced them.
+    pub fn rule_counts(&self) -> BTreeMap<PriceRule, u32> {
+        let mut counts = BTreeMap::new();
+
+        for item in self.items.values() {
+            *counts.entry(item.rule).or_default() += item.count;
+        }
+
+        counts
+    }
+}
+
+/// Prices a SKU by the first rule that finds a price.
+fn price(sku: &SKU, prices: &PriceTable, side: PriceSide) -> Option<(Currencies, PriceRule, SKU)> {
+    let lookup = |sku: &SKU| prices.get(sku).map(|price| match side {
+        PriceSide::Buy => price.buy,
+        PriceSide::Sell => price.sell,
+    });
+
+    if let Some(price) = lookup(sku) {
+        return Some((price, PriceRule::Exact, *sku));
+    }
+
+    if let Some(price) = Currencies::from_sku(sku) {
+        return Some((price, PriceRule::Currency, *sku));
+    }
+
+    let without_extras = SKU {
+        craft_number: None,
+        paint: None,
+        spells: Default::default(),
+        strange_parts: Default::default(),
+        ..*sku
+    };
+    let without_killstreak = SKU {
+        killstreak_tier: None,
+        sheen: None,
+        killstreaker: None,
+        ..without_extras
+    };
+
+    [
+        (without_extras, PriceRule::WithoutExtras),
+        (without_killstreak, PriceRule::WithoutKillstreak),
+    ]
+        .into_iter()
+        .filter(|(candidate, _)| candidate != sku)
+        .find_map(|(candidate, rule)| lookup(&candidate).map(|price| (price, rule, candidate)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+    use crate::price_table::ItemPrice;
+
+    fn sku(sku: &str) -> SKU {
+        sku.parse().unwrap()
+    }
+
+    fn prices() -> PriceTable {
+        [
+            ("5021;6", "60.33 ref", "60.44 ref"),
+            ("378;5;u13", "210 keys", "250 keys"),
+            ("205;11", "3 ref", "4 ref"),
+            ("205;11;kt-3", "1 key", "1 key, 10 ref"),
+        ]
+            .into_iter()
+            .map(|(item, buy, sell)| (sku(item), ItemPrice {
+                buy: buy.parse().unwrap(),
+                sell: sell.parse().unwrap(),
+            }))
+            .collect()
+    }
+
+    fn inventory(items: &[(&str, u32)]) -> HashMap<SKU, u32> {
+        items.iter().map(|(item, count)| (sku(item), *count)).collect()
+    }
+
+    #[test]
+    fn values_inventories() {
+        let inventory = inventory(&[
+            ("5021;6", 3),
+            ("5002;6", 4),
+            ("378;5;u13", 1),
+            ("205;11;kt-3", 2),
+            ("205;11;n42", 1),
+            ("205;11;kt-2;ks-1;ke-2008;sp-28", 1),
+            ("200;6", 5),
+            ("378;5;u14", 1),
+        ]);
+        let valuation = Valuation::new(&inventory, &prices(), &schema(), PriceSide::Buy);
+        let rule = |item: &str| valuation.items[&sku(item)].rule;
+
+        assert_eq!(valuation.total, "212 keys, 191 ref".parse().unwrap());
+        assert_eq!(rule("5021;6"), PriceRule::Exact);
+        assert_eq!(rule("5002;6"), PriceRule::Currency);
+        assert_eq!(rule("205;11;n42"), PriceRule::WithoutExtras);
+        assert_eq!(rule("205;11;kt-2;ks-1;ke-2008;sp-28"), PriceRule::WithoutKillstreak);
+        assert_eq!(valuation.items[&sku("205;11;kt-2;ks-1;ke-2008;sp-28")].priced_as, sku("205;11"));
+        assert_eq!(valuation.unpriced, BTreeMap::from([(sku("200;6"), 5), (sku("378;5;u14"), 1)]));
+        assert_eq!(valuation.rule_counts()[&PriceRule::Exact], 6);
+    }
+
+    #[test]
+    fn breaks_down_value() {
+        let inventory = inventory(&[("378;5;u13", 1), ("205;11;kt-3", 2), ("205;11", 1), ("5002;6", 1)]);
+        let valuation = Valuation::new(&inventory, &prices(), &schema(), PriceSide::Sell);
+
+        assert_eq!(valuation.by_quality[&Quality::Unusual], Currencies::new(250, 0));
+        assert_eq!(valuation.by_quality[&Quality::Strange], "2 keys, 24 ref".parse().unwrap());
+        assert_eq!(valuation.by_item_class[&Some("tf_wearable".into())], Currencies::new(250, 0));
+        assert_eq!(valuation.by_item_class[&Some("craft_item".into())], Currencies::new(0, 18));
+        assert_eq!(valuation.by_killstreak_tier[&Some(KillstreakTier::Professional)], "2 keys, 20 ref".parse().unwrap());
+        assert_eq!(valuation.by_killstreak_tier[&None], Currencies::new(250, 5 * 18));
+    }
+
+    #[test]
+    fn merges_valuations() {
+        let prices = prices();
+        let schema = schema();
+        let first = inventory(&[("5021;6", 1), ("200;6", 1)]);
+        let second = inventory(&[("5021;6", 2), ("200;6", 3), ("5002;6", 1)]);
+        let mut valuation = Valuation::new(&first, &prices, &schema, PriceSide::Buy);
+
+        valuation.merge(&Valuation::new(&second, &prices, &schema, PriceSide::Buy));
+
+        let combined = first
+            .iter()
+            .chain(&second)
+            .fold(HashMap::new(), |mut inventory, (sku, count)| {
+                *inventory.entry(*sku).or_insert(0) += count;
+                inventory
+            });
+
+        assert_eq!(valuation, Valuation::new(&combined, &prices, &schema, PriceSide::Buy));
+    }
+
+    #[test]
+    fn merges_valuations_priced_differently() {
+        let schema = schema();
+        let inventory = inventory(&[("5021;6", 2), ("205;11", 1)]);
+        let mut valuation = Valuation::new(&inventory, &prices(), &schema, PriceSide::Buy);
+
+        valuation.merge(&Valuation::new(&inventory, &prices(), &schema, PriceSide::Sell));
+
+        let key = &valuation.items[&sku("5021;6")];
+
+        assert_eq!(key.count, 4);
+        assert_eq!(key.price, "60.33 ref".parse().unwrap());
+        assert_eq!(key.value, "241.54 ref".parse().unwrap());
+        assert_eq!(valuation.items.values().map(|item| item.value).sum::<Currencies>(), valuation.total);
+    }
+}
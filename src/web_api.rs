@@ -0,0 +1,286 @@
+//! Models for the Steam Web API's `IEconItems_440/GetPlayerItems` response, and conversion of its
+//! items into SKUs.
+
+use crate::SKU;
+use crate::attributes::apply_attribute;
+use crate::error::ItemError;
+use crate::schema::ItemSchema;
+use serde::{Deserialize, Serialize};
+use tf2_enum::econ_attributes::ToolTargetItem;
+use tf2_enum::{Attribute, AttributeValue, ItemAttribute, KillstreakTier, Quality};
+
+/// The response of `IEconItems_440/GetPlayerItems`.
+///
+/// # Examples
+/// ```
+/// use tf2_sku::SKU;
+/// use tf2_sku::web_api::GetPlayerItemsResponse;
+///
+/// let json = r#"{
+///     "result": {
+///         "status": 1,
+///         "items": [{ "id": 1, "original_id": 1, "defindex": 5021, "level": 5, "quality": 6, "inventory": 1, "quantity": 1, "origin": 0 }]
+///     }
+/// }"#;
+/// let response = serde_json::from_str::<GetPlayerItemsResponse>(json).unwrap();
+/// let sku = SKU::try_from(&response.result.items[0]).unwrap();
+///
+/// assert_eq!(sku.to_string(), "5021;6");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPlayerItemsResponse {
+    /// The result.
+    pub result: PlayerItems,
+}
+
+/// The backpack of a player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerItems {
+    /// The status of the response. 1 is success, 8 is an invalid Steam ID, 15 is a private
+    /// backpack and 18 is a Steam ID that doesn't exist.
+    pub status: u32,
+    /// The number of backpack slots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_backpack_slots: Option<u32>,
+    /// A description of the status when it isn't a success.
+    #[serde(default, rename = "statusDetail", skip_serializing_if = "Option::is_none")]
+    pub status_detail: Option<String>,
+    /// The items in the backpack.
+    #[serde(default)]
+    pub items: Vec<EconItem>,
+}
+
+/// An item in a backpack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconItem {
+    /// The item's ID.
+    pub id: u64,
+    /// The item's original ID, before it last changed e.g. from being painted.
+    pub original_id: u64,
+    /// The defindex.
+    pub defindex: u32,
+    /// The level.
+    pub level: u32,
+    /// The quality.
+    pub quality: u32,
+    /// The position in the backpack, and whether the item is new.
+    pub inventory: u32,
+    /// The quantity, which is 1 for anything other than some tools.
+    pub quantity: u32,
+    /// How the item was obtained.
+    #[serde(default)]
+    pub origin: u32,
+    /// Whether the item cannot be traded.
+    #[serde(default)]
+    pub flag_cannot_trade: bool,
+    /// Whether the item cannot be crafted.
+    #[serde(default)]
+    pub flag_cannot_craft: bool,
+    /// The name given with a Name Tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_name: Option<String>,
+    /// The description given with a Description Tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_desc: Option<String>,
+    /// The style.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<u32>,
+    /// The attributes.
+    #[serde(default)]
+    pub attributes: Vec<EconItemAttribute>,
+    /// The classes and slots the item is equipped in.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equipped: Vec<Equipped>,
+}
+
+/// An attribute of an item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconItemAttribute {
+    /// The defindex of the attribute.
+    pub defindex: u32,
+    /// The value.
+    #[serde(default)]
+    pub value: AttributeValue,
+    /// The value as a float.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub float_value: Option<f32>,
+    /// The account the attribute refers to e.g. the gifter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_info: Option<AccountInfo>,
+    /// Whether this is the output of a recipe e.g. a Chemistry Set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_output: Option<bool>,
+    /// The defindex of a recipe's input or output item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub itemdef: Option<u32>,
+    /// The quantity of a recipe's input or output item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u32>,
+    /// The quality of a recipe's input or output item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u32>,
+    /// Whether a recipe's input must match every attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_all_attribs: Option<bool>,
+    /// The attributes of a recipe's input or output item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<EconItemAttribute>,
+}
+
+impl From<&EconItemAttribute> for ItemAttribute {
+    fn from(attribute: &EconItemAttribute) -> Self {
+        ItemAttribute {
+            defindex: attribute.defindex,
+            value: attribute.value.clone(),
+            float_value: attribute.float_value,
+        }
+    }
+}
+
+/// An account referred to by an attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountInfo {
+    /// The 64-bit Steam ID.
+    pub steamid: u64,
+    /// The name of the account.
+    pub personaname: String,
+}
+
+/// A class and slot an item is equipped in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Equipped {
+    /// The class.
+    pub class: u32,
+    /// The slot.
+    pub slot: u32,
+}
+
+impl TryFrom<&EconItem> for SKU {
+    type Error = ItemError;
+
+    /// Converts an item into a SKU from its own attributes. Use [`SKU::from_econ_item`] to also
+    /// fill in what the schema defines for the item.
+    fn try_from(item: &EconItem) -> Result<Self, Self::Error> {
+        let defindex = i32::try_from(item.defindex)
+            .map_err(|_| ItemError::InvalidDefindex(item.defindex))?;
+        let quality = Quality::try_from(item.quality)
+            .map_err(|_| ItemError::UnknownQuality(item.quality))?;
+        let attributes = item.attributes
+            .iter()
+            .filter(|attribute| attribute.is_output.is_none())
+            .map(ItemAttribute::from)
+            .collect::<Vec<_>>();
+        let mut sku = SKU::from_attributes(defindex, quality, &attributes);
+
+        sku.craftable = !item.flag_cannot_craft;
+
+        // The output of a recipe e.g. "Strangifier" for a Strangifier Chemistry Set. Its target
+        // and killstreak tier belong to the recipe.
+        if let Some(output) = item.attributes.iter().find(|attribute| attribute.is_output == Some(true)) {
+            sku.output_defindex = output.itemdef;
+            sku.output_quality = output.quality.and_then(|quality| Quality::try_from(quality).ok());
+
+            for attribute in &output.attributes {
+                if matches!(attribute.defindex, ToolTargetItem::DEFINDEX | KillstreakTier::DEFINDEX) {
+                    apply_attribute(&mut sku, &attribute.into());
+                }
+            }
+        }
+
+        Ok(sku)
+    }
+}
+
+impl SKU {
+    /// Converts an item into a SKU, filling in what the schema defines for the item rather than
+    /// its attributes: the war paint of pre-painted items and the series of crates with a fixed
+    /// series. Items that are Strange by definition, such as those with a kill counter built in,
+    /// aren't elevated.
+    ///
+    /// # Errors
+    /// The same as [`SKU::try_from`].
+    pub fn from_econ_item<S: ItemSchema + ?Sized>(
+        item: &EconItem,
+        schema: &S,
+    ) -> Result<Self, ItemError> {
+        let mut sku = SKU::try_from(item)?;
+
+        if let Some(schema_item) = schema.sku_item(&sku) {
+            sku.skin = sku.skin.or(schema_item.paint_kit);
+            sku.crate_number = sku.crate_number.or(schema_item.crate_series);
+
+            if schema_item.item_quality == Quality::Strange {
+                sku.strange = false;
+            }
+        }
+
+        Ok(sku)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::tests::schema;
+
+    fn response() -> GetPlayerItemsResponse {
+        let json = std::fs::read_to_string("tests/fixtures/get_player_items.json").unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn deserializes_response() {
+        let response = response();
+        let item = &response.result.items[1];
+
+        assert_eq!(response.result.status, 1);
+        assert_eq!(response.result.items.len(), 8);
+        assert_eq!(item.custom_name.as_deref(), Some("Hat of Flames"));
+        assert!(item.flag_cannot_trade);
+        assert_eq!(item.equipped, [Equipped { class: 3, slot: 7 }]);
+        assert_eq!(item.attributes[4].account_info.as_ref().unwrap().personaname, "Gifter");
+    }
+
+    #[test]
+    fn converts_items() {
+        let skus = response().result.items
+            .iter()
+            .map(|item| SKU::try_from(item).unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(skus, [
+            "205;11;australium;kt-3;ks-1;ke-2008;sp-28",
+            "378;5;u13;uncraftable;strange;p8421376;footprints-2",
+            "16102;15;w1;pk102",
+            "20005;6;td-378;od-6522;oq-6",
+            "5022;6;c1",
+            "199;6;n100",
+            "16102;15;w3",
+            "20002;6;kt-2;td-205;od-6526;oq-6",
+        ]);
+    }
+
+    #[test]
+    fn fills_in_from_schema() {
+        let response = response();
+        let sku = SKU::from_econ_item(&response.result.items[6], &schema()).unwrap();
+
+        assert_eq!(sku.to_string(), "16102;15;w3;pk102");
+
+        let mut item = response.result.items[4].clone();
+
+        item.attributes.clear();
+
+        assert_eq!(SKU::from_econ_item(&item, &schema()).unwrap().to_string(), "5022;6;c1");
+    }
+
+    #[test]
+    fn rejects_unknown_qualities() {
+        let mut item = response().result.items[0].clone();
+
+        item.quality = 100;
+
+        assert_eq!(SKU::try_from(&item), Err(ItemError::UnknownQuality(100)));
+    }
+}
@@ -0,0 +1,38 @@
+// @generated by tf2-sku-codegen. Do not edit.
+
+static ITEMS: ::phf::Map<u32, crate::schema::SchemaItem> = ::phf::Map {
+    key: 10121458955350035957,
+    disps: &[
+        (0, 0),
+        (0, 0),
+    ],
+    entries: &[
+        (6526, crate::schema::SchemaItem { defindex: 6526, name: ::std::borrow::Cow::Borrowed("Killstreak Item Professional"), item_name: ::std::borrow::Cow::Borrowed("#TF_KillStreakifierToolB"), proper_name: false, item_class: ::std::borrow::Cow::Borrowed("tool"), item_quality: crate::tf2_enum::Quality::Unique, craft_class: Some(crate::tf2_enum::CraftClass::Tool), item_slot: None, tool_type: Some(::std::borrow::Cow::Borrowed("killstreakifier")), crate_series: None, paint_kit: None, capabilities: ::std::borrow::Cow::Borrowed(&[crate::tf2_enum::Capability::Nameable, crate::tf2_enum::Capability::CanGiftWrap]) }),
+        (205, crate::schema::SchemaItem { defindex: 205, name: ::std::borrow::Cow::Borrowed("Upgradeable TF_WEAPON_ROCKETLAUNCHER"), item_name: ::std::borrow::Cow::Borrowed("#TF_Weapon_RocketLauncher"), proper_name: false, item_class: ::std::borrow::Cow::Borrowed("tf_weapon_rocketlauncher"), item_quality: crate::tf2_enum::Quality::Unique, craft_class: Some(crate::tf2_enum::CraftClass::Weapon), item_slot: Some(crate::tf2_enum::ItemSlot::Primary), tool_type: None, crate_series: None, paint_kit: None, capabilities: ::std::borrow::Cow::Borrowed(&[crate::tf2_enum::Capability::Nameable, crate::tf2_enum::Capability::CanGiftWrap, crate::tf2_enum::Capability::CanKillstreakify, crate::tf2_enum::Capability::CanStrangify, crate::tf2_enum::Capability::StrangeParts]) }),
+        (378, crate::schema::SchemaItem { defindex: 378, name: ::std::borrow::Cow::Borrowed("The Team Captain"), item_name: ::std::borrow::Cow::Borrowed("#TF_SoldierHat1"), proper_name: true, item_class: ::std::borrow::Cow::Borrowed("tf_wearable"), item_quality: crate::tf2_enum::Quality::Unique, craft_class: Some(crate::tf2_enum::CraftClass::Hat), item_slot: Some(crate::tf2_enum::ItemSlot::Misc), tool_type: None, crate_series: None, paint_kit: None, capabilities: ::std::borrow::Cow::Borrowed(&[crate::tf2_enum::Capability::Nameable, crate::tf2_enum::Capability::CanGiftWrap, crate::tf2_enum::Capability::Paintable]) }),
+        (18, crate::schema::SchemaItem { defindex: 18, name: ::std::borrow::Cow::Borrowed("TF_WEAPON_ROCKETLAUNCHER"), item_name: ::std::borrow::Cow::Borrowed("#TF_Weapon_RocketLauncher"), proper_name: false, item_class: ::std::borrow::Cow::Borrowed("tf_weapon_rocketlauncher"), item_quality: crate::tf2_enum::Quality::Normal, craft_class: Some(crate::tf2_enum::CraftClass::Weapon), item_slot: Some(crate::tf2_enum::ItemSlot::Primary), tool_type: None, crate_series: None, paint_kit: None, capabilities: ::std::borrow::Cow::Borrowed(&[crate::tf2_enum::Capability::Nameable, crate::tf2_enum::Capability::CanGiftWrap, crate::tf2_enum::Capability::CanKillstreakify, crate::tf2_enum::Capability::CanStrangify, crate::tf2_enum::Capability::StrangeParts]) }),
+        (16310, crate::schema::SchemaItem { defindex: 16310, name: ::std::borrow::Cow::Borrowed("Paintkit Rocket Launcher Hypno-Beam"), item_name: ::std::borrow::Cow::Borrowed("#TF_Weapon_RocketLauncher"), proper_name: false, item_class: ::std::borrow::Cow::Borrowed("tf_weapon_rocketlauncher"), item_quality: crate::tf2_enum::Quality::DecoratedWeapon, craft_class: Some(crate::tf2_enum::CraftClass::Weapon), item_slot: Some(crate::tf2_enum::ItemSlot::Primary), tool_type: None, crate_series: None, paint_kit: Some(310), capabilities: ::std::borrow::Cow::Borrowed(&[crate::tf2_enum::Capability::Nameable, crate::tf2_enum::Capability::CanGiftWrap, crate::tf2_enum::Capability::CanKillstreakify, crate::tf2_enum::Capability::CanStrangify, crate::tf2_enum::Capability::StrangeParts]) }),
+        (5022, crate::schema::SchemaItem { defindex: 5022, name: ::std::borrow::Cow::Borrowed("Supply Crate 1"), item_name: ::std::borrow::Cow::Borrowed("#TF_SupplyCrate"), proper_name: false, item_class: ::std::borrow::Cow::Borrowed("supply_crate"), item_quality: crate::tf2_enum::Quality::Unique, craft_class: Some(crate::tf2_enum::CraftClass::SupplyCrate), item_slot: None, tool_type: None, crate_series: Some(1), paint_kit: None, capabilities: ::std::borrow::Cow::Borrowed(&[crate::tf2_enum::Capability::Nameable, crate::tf2_enum::Capability::CanGiftWrap]) }),
+    ],
+};
+
+static PARTICLES: ::phf::Map<u32, &'static str> = ::phf::Map {
+    key: 12913932095322966823,
+    disps: &[
+        (1, 0),
+    ],
+    entries: &[
+        (703, "#Attrib_Particle703"),
+        (13, "#Attrib_Particle13"),
+    ],
+};
+
+static PAINT_KITS: ::phf::Map<u32, &'static str> = ::phf::Map {
+    key: 12913932095322966823,
+    disps: &[
+        (0, 0),
+    ],
+    entries: &[
+        (310, "#9_310_field"),
+    ],
+};